@@ -0,0 +1,4 @@
+drop table follows;
+drop table tweets;
+drop table auth_tokens;
+drop table users;
@@ -35,4 +35,3 @@ create table follows (
 );
 
 create unique index follows_follower_followee on follows(follower_id, followee_id);
-
@@ -0,0 +1,7 @@
+drop index auth_tokens_user_id;
+
+alter table auth_tokens
+    drop column expires_at,
+    drop column last_used_at,
+    drop column user_agent,
+    drop column ip_address;
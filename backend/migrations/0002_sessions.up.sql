@@ -0,0 +1,17 @@
+alter table auth_tokens
+    add column expires_at timestamp with time zone,
+    add column last_used_at timestamp with time zone,
+    add column user_agent varchar,
+    add column ip_address varchar;
+
+update auth_tokens
+set expires_at = now() + make_interval(
+        secs => current_setting('witter.session_ttl_seconds')::double precision
+    ),
+    last_used_at = updated_at;
+
+alter table auth_tokens
+    alter column expires_at set not null,
+    alter column last_used_at set not null;
+
+create index auth_tokens_user_id on auth_tokens(user_id);
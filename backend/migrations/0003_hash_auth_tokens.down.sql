@@ -0,0 +1,8 @@
+-- Hashes can't be turned back into tokens, so everyone gets signed out.
+delete from auth_tokens;
+
+drop index auth_tokens_token_hash;
+alter table auth_tokens drop column token_hash;
+
+alter table auth_tokens add column token varchar not null;
+create unique index auth_tokens_token on auth_tokens(token);
@@ -0,0 +1,16 @@
+-- Existing tokens are rehashed in place with the same HMAC-SHA256 the backend
+-- uses. The migration runner exposes `SECRET_KEY` as `witter.secret_key` for
+-- the duration of the transaction.
+create extension if not exists pgcrypto;
+
+alter table auth_tokens add column token_hash varchar;
+
+update auth_tokens
+set token_hash = encode(hmac(token, current_setting('witter.secret_key'), 'sha256'), 'hex');
+
+alter table auth_tokens alter column token_hash set not null;
+
+drop index auth_tokens_token;
+alter table auth_tokens drop column token;
+
+create unique index auth_tokens_token_hash on auth_tokens(token_hash);
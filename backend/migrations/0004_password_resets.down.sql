@@ -0,0 +1,4 @@
+drop table password_resets;
+
+drop index users_email;
+alter table users drop column email;
@@ -0,0 +1,15 @@
+alter table users add column email varchar;
+
+create unique index users_email on users(lower(email));
+
+create table password_resets (
+    id uuid primary key,
+    user_id uuid not null references users (id),
+    token_hash varchar not null,
+    expires_at timestamp with time zone not null,
+    used_at timestamp with time zone,
+    created_at timestamp with time zone not null,
+    updated_at timestamp with time zone not null
+);
+
+create unique index password_resets_token_hash on password_resets(token_hash);
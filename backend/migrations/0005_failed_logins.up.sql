@@ -0,0 +1,10 @@
+create table failed_logins (
+    id uuid primary key,
+    username varchar not null,
+    ip_address varchar,
+    created_at timestamp with time zone not null
+);
+
+create index failed_logins_username on failed_logins(lower(username), created_at);
+create index failed_logins_ip_address on failed_logins(ip_address, created_at);
+create index failed_logins_created_at on failed_logins(created_at);
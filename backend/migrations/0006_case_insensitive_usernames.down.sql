@@ -0,0 +1,3 @@
+drop index users_username;
+
+create unique index users_username on users(username);
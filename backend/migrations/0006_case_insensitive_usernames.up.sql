@@ -0,0 +1,29 @@
+-- Usernames that only differ by case can't share the new index. The oldest
+-- account keeps its name and the others get part of their id appended, with
+-- a notice so whoever runs the migration knows which accounts to follow up.
+do $$
+declare
+    duplicate record;
+    new_username varchar;
+begin
+    for duplicate in
+        select id, username
+        from (
+            select
+                id
+                , username
+                , row_number() over (partition by lower(username) order by created_at, id) as position
+            from users
+        ) as ranked
+        where position > 1
+    loop
+        new_username := duplicate.username || '_' || left(duplicate.id::text, 8);
+        update users set username = new_username where id = duplicate.id;
+        raise notice 'renamed user % from % to %', duplicate.id, duplicate.username, new_username;
+    end loop;
+end
+$$;
+
+drop index users_username;
+
+create unique index users_username on users(lower(username));
@@ -0,0 +1,25 @@
+alter table password_resets
+    drop constraint password_resets_user_id_fkey,
+    add constraint password_resets_user_id_fkey
+        foreign key (user_id) references users (id);
+
+alter table follows
+    drop constraint follows_followee_id_fkey,
+    add constraint follows_followee_id_fkey
+        foreign key (followee_id) references users (id),
+    drop constraint follows_follower_id_fkey,
+    add constraint follows_follower_id_fkey
+        foreign key (follower_id) references users (id);
+
+alter table tweets
+    drop constraint tweets_user_id_fkey,
+    add constraint tweets_user_id_fkey
+        foreign key (user_id) references users (id);
+
+alter table auth_tokens
+    drop constraint auth_tokens_user_id_fkey,
+    add constraint auth_tokens_user_id_fkey
+        foreign key (user_id) references users (id);
+
+drop index users_deactivated_at;
+alter table users drop column deactivated_at;
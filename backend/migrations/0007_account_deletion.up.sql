@@ -0,0 +1,26 @@
+alter table users add column deactivated_at timestamp with time zone;
+
+create index users_deactivated_at on users(deactivated_at) where deactivated_at is not null;
+
+alter table auth_tokens
+    drop constraint auth_tokens_user_id_fkey,
+    add constraint auth_tokens_user_id_fkey
+        foreign key (user_id) references users (id) on delete cascade;
+
+alter table tweets
+    drop constraint tweets_user_id_fkey,
+    add constraint tweets_user_id_fkey
+        foreign key (user_id) references users (id) on delete cascade;
+
+alter table follows
+    drop constraint follows_follower_id_fkey,
+    add constraint follows_follower_id_fkey
+        foreign key (follower_id) references users (id) on delete cascade,
+    drop constraint follows_followee_id_fkey,
+    add constraint follows_followee_id_fkey
+        foreign key (followee_id) references users (id) on delete cascade;
+
+alter table password_resets
+    drop constraint password_resets_user_id_fkey,
+    add constraint password_resets_user_id_fkey
+        foreign key (user_id) references users (id) on delete cascade;
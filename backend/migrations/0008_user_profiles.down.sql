@@ -0,0 +1,6 @@
+alter table users
+    drop column display_name,
+    drop column bio,
+    drop column location,
+    drop column website,
+    drop column avatar_url;
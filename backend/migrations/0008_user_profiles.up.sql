@@ -0,0 +1,6 @@
+alter table users
+    add column display_name varchar,
+    add column bio varchar,
+    add column location varchar,
+    add column website varchar,
+    add column avatar_url varchar;
@@ -0,0 +1,5 @@
+alter table users
+    drop column avatar_thumbnail_url,
+    drop column header_url,
+    drop column avatar_key,
+    drop column header_key;
@@ -0,0 +1,5 @@
+alter table users
+    add column avatar_thumbnail_url varchar,
+    add column header_url varchar,
+    add column avatar_key varchar,
+    add column header_key varchar;
@@ -0,0 +1,3 @@
+drop table follow_requests;
+
+alter table users drop column protected;
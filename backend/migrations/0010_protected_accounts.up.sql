@@ -0,0 +1,12 @@
+alter table users add column protected boolean not null default false;
+
+create table follow_requests (
+    id uuid primary key,
+    requester_id uuid not null references users (id) on delete cascade,
+    target_id uuid not null references users (id) on delete cascade,
+    created_at timestamp with time zone not null,
+    updated_at timestamp with time zone not null
+);
+
+create unique index follow_requests_requester_target on follow_requests(requester_id, target_id);
+create index follow_requests_target_id on follow_requests(target_id, created_at);
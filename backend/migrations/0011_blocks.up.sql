@@ -0,0 +1,10 @@
+create table blocks (
+    id uuid primary key,
+    blocker_id uuid not null references users (id) on delete cascade,
+    blocked_id uuid not null references users (id) on delete cascade,
+    created_at timestamp with time zone not null,
+    updated_at timestamp with time zone not null
+);
+
+create unique index blocks_blocker_blocked on blocks(blocker_id, blocked_id);
+create index blocks_blocked_id on blocks(blocked_id);
@@ -0,0 +1,2 @@
+drop table muted_keywords;
+drop table muted_users;
@@ -0,0 +1,21 @@
+create table muted_users (
+    id uuid primary key,
+    user_id uuid not null references users (id) on delete cascade,
+    muted_user_id uuid not null references users (id) on delete cascade,
+    expires_at timestamp with time zone,
+    created_at timestamp with time zone not null,
+    updated_at timestamp with time zone not null
+);
+
+create unique index muted_users_user_muted_user on muted_users(user_id, muted_user_id);
+
+create table muted_keywords (
+    id uuid primary key,
+    user_id uuid not null references users (id) on delete cascade,
+    keyword text not null,
+    expires_at timestamp with time zone,
+    created_at timestamp with time zone not null,
+    updated_at timestamp with time zone not null
+);
+
+create unique index muted_keywords_user_keyword on muted_keywords(user_id, lower(keyword));
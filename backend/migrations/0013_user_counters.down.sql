@@ -0,0 +1,9 @@
+drop trigger tweets_update_counts on tweets;
+drop function update_tweet_counts();
+drop trigger follows_update_counts on follows;
+drop function update_follow_counts();
+
+alter table users
+    drop column followers_count,
+    drop column following_count,
+    drop column tweets_count;
@@ -0,0 +1,43 @@
+alter table users
+    add column followers_count bigint not null default 0,
+    add column following_count bigint not null default 0,
+    add column tweets_count bigint not null default 0;
+
+update users set
+    followers_count = (select count(*) from follows where follows.followee_id = users.id),
+    following_count = (select count(*) from follows where follows.follower_id = users.id),
+    tweets_count = (select count(*) from tweets where tweets.user_id = users.id);
+
+-- Kept up to date here rather than in the endpoints so cascading deletes
+-- are counted too
+create function update_follow_counts() returns trigger as $$
+begin
+    if tg_op = 'INSERT' then
+        update users set following_count = following_count + 1 where id = new.follower_id;
+        update users set followers_count = followers_count + 1 where id = new.followee_id;
+    else
+        update users set following_count = following_count - 1 where id = old.follower_id;
+        update users set followers_count = followers_count - 1 where id = old.followee_id;
+    end if;
+    return null;
+end;
+$$ language plpgsql;
+
+create trigger follows_update_counts
+    after insert or delete on follows
+    for each row execute procedure update_follow_counts();
+
+create function update_tweet_counts() returns trigger as $$
+begin
+    if tg_op = 'INSERT' then
+        update users set tweets_count = tweets_count + 1 where id = new.user_id;
+    else
+        update users set tweets_count = tweets_count - 1 where id = old.user_id;
+    end if;
+    return null;
+end;
+$$ language plpgsql;
+
+create trigger tweets_update_counts
+    after insert or delete on tweets
+    for each row execute procedure update_tweet_counts();
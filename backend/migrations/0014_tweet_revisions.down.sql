@@ -0,0 +1,3 @@
+alter table tweets drop column revisions_count;
+
+drop table tweet_revisions;
@@ -0,0 +1,12 @@
+-- Earlier texts of edited tweets. `created_at` is when that text was posted,
+-- the tweet's `updated_at` at the time it was replaced
+create table tweet_revisions (
+    id uuid primary key default gen_random_uuid(),
+    tweet_id uuid not null references tweets (id) on delete cascade,
+    text text not null,
+    created_at timestamp with time zone not null
+);
+
+create index tweet_revisions_tweet_id on tweet_revisions(tweet_id, created_at);
+
+alter table tweets add column revisions_count bigint not null default 0;
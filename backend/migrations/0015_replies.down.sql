@@ -0,0 +1,7 @@
+drop trigger tweets_update_reply_counts on tweets;
+drop function update_reply_counts();
+
+alter table tweets
+    drop column replies_count,
+    drop column conversation_id,
+    drop column in_reply_to_id;
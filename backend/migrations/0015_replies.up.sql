@@ -0,0 +1,27 @@
+alter table tweets
+    add column in_reply_to_id uuid references tweets (id) on delete set null,
+    add column conversation_id uuid,
+    add column replies_count bigint not null default 0;
+
+-- Every existing tweet starts its own conversation. Not a foreign key, so a
+-- conversation keeps going after its first tweet is deleted
+update tweets set conversation_id = id;
+alter table tweets alter column conversation_id set not null;
+
+create index tweets_in_reply_to_id on tweets(in_reply_to_id);
+create index tweets_conversation_id on tweets(conversation_id);
+
+create function update_reply_counts() returns trigger as $$
+begin
+    if tg_op = 'INSERT' then
+        update tweets set replies_count = replies_count + 1 where id = new.in_reply_to_id;
+    else
+        update tweets set replies_count = replies_count - 1 where id = old.in_reply_to_id;
+    end if;
+    return null;
+end;
+$$ language plpgsql;
+
+create trigger tweets_update_reply_counts
+    after insert or delete on tweets
+    for each row execute procedure update_reply_counts();
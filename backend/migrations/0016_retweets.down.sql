@@ -0,0 +1,8 @@
+drop trigger retweets_update_counts on retweets;
+drop function update_retweet_counts();
+
+alter table tweets
+    drop column retweets_count,
+    drop column quoted_tweet_id;
+
+drop table retweets;
@@ -0,0 +1,29 @@
+create table retweets (
+    id uuid primary key default gen_random_uuid(),
+    user_id uuid not null references users (id) on delete cascade,
+    tweet_id uuid not null references tweets (id) on delete cascade,
+    created_at timestamp with time zone not null
+);
+
+create unique index retweets_user_tweet on retweets(user_id, tweet_id);
+create index retweets_tweet_id on retweets(tweet_id);
+
+-- Quotes have their own text, so they outlive the tweet they quote
+alter table tweets
+    add column quoted_tweet_id uuid references tweets (id) on delete set null,
+    add column retweets_count bigint not null default 0;
+
+create function update_retweet_counts() returns trigger as $$
+begin
+    if tg_op = 'INSERT' then
+        update tweets set retweets_count = retweets_count + 1 where id = new.tweet_id;
+    else
+        update tweets set retweets_count = retweets_count - 1 where id = old.tweet_id;
+    end if;
+    return null;
+end;
+$$ language plpgsql;
+
+create trigger retweets_update_counts
+    after insert or delete on retweets
+    for each row execute procedure update_retweet_counts();
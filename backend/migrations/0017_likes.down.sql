@@ -0,0 +1,6 @@
+drop trigger likes_update_counts on likes;
+drop function update_like_counts();
+
+alter table tweets drop column likes_count;
+
+drop table likes;
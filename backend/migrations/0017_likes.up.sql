@@ -0,0 +1,27 @@
+create table likes (
+    id uuid primary key default gen_random_uuid(),
+    user_id uuid not null references users (id) on delete cascade,
+    tweet_id uuid not null references tweets (id) on delete cascade,
+    created_at timestamp with time zone not null
+);
+
+create unique index likes_user_tweet on likes(user_id, tweet_id);
+create index likes_user_id_created_at on likes(user_id, created_at);
+create index likes_tweet_id on likes(tweet_id);
+
+alter table tweets add column likes_count bigint not null default 0;
+
+create function update_like_counts() returns trigger as $$
+begin
+    if tg_op = 'INSERT' then
+        update tweets set likes_count = likes_count + 1 where id = new.tweet_id;
+    else
+        update tweets set likes_count = likes_count - 1 where id = old.tweet_id;
+    end if;
+    return null;
+end;
+$$ language plpgsql;
+
+create trigger likes_update_counts
+    after insert or delete on likes
+    for each row execute procedure update_like_counts();
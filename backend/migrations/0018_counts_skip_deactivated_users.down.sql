@@ -0,0 +1,19 @@
+drop trigger users_update_follow_counts on users;
+drop function update_follow_counts_on_deactivation();
+
+create or replace function update_follow_counts() returns trigger as $$
+begin
+    if tg_op = 'INSERT' then
+        update users set following_count = following_count + 1 where id = new.follower_id;
+        update users set followers_count = followers_count + 1 where id = new.followee_id;
+    else
+        update users set following_count = following_count - 1 where id = old.follower_id;
+        update users set followers_count = followers_count - 1 where id = old.followee_id;
+    end if;
+    return null;
+end;
+$$ language plpgsql;
+
+update users set
+    followers_count = (select count(*) from follows where follows.followee_id = users.id),
+    following_count = (select count(*) from follows where follows.follower_id = users.id);
@@ -0,0 +1,72 @@
+-- Deactivated users are left out of follower lists, so they're left out of
+-- the counts too. Their follows come off the other side's counts when they
+-- deactivate and go back on if they log in again.
+update users set
+    followers_count = (
+        select count(*)
+        from follows
+        inner join users as followers on followers.id = follows.follower_id
+        where follows.followee_id = users.id and followers.deactivated_at is null
+    ),
+    following_count = (
+        select count(*)
+        from follows
+        inner join users as followees on followees.id = follows.followee_id
+        where follows.follower_id = users.id and followees.deactivated_at is null
+    );
+
+-- A follow only counts while the other side is active. By the time a purge
+-- cascades to `follows` the user is gone, and was deactivated beforehand, so
+-- those deletes leave the counts alone.
+create or replace function update_follow_counts() returns trigger as $$
+declare
+    follow follows%rowtype;
+    change bigint;
+begin
+    if tg_op = 'INSERT' then
+        follow := new;
+        change := 1;
+    else
+        follow := old;
+        change := -1;
+    end if;
+
+    update users set following_count = following_count + change
+    where id = follow.follower_id
+        and exists (
+            select 1 from users as followees
+            where followees.id = follow.followee_id and followees.deactivated_at is null
+        );
+    update users set followers_count = followers_count + change
+    where id = follow.followee_id
+        and exists (
+            select 1 from users as followers
+            where followers.id = follow.follower_id and followers.deactivated_at is null
+        );
+    return null;
+end;
+$$ language plpgsql;
+
+create function update_follow_counts_on_deactivation() returns trigger as $$
+declare
+    change bigint;
+begin
+    if new.deactivated_at is null then
+        change := 1;
+    else
+        change := -1;
+    end if;
+
+    update users set followers_count = followers_count + change
+    where id in (select followee_id from follows where follower_id = new.id);
+    update users set following_count = following_count + change
+    where id in (select follower_id from follows where followee_id = new.id);
+    return null;
+end;
+$$ language plpgsql;
+
+create trigger users_update_follow_counts
+    after update of deactivated_at on users
+    for each row
+    when ((old.deactivated_at is null) <> (new.deactivated_at is null))
+    execute procedure update_follow_counts_on_deactivation();
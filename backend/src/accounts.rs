@@ -0,0 +1,104 @@
+//! Deleting accounts. `DELETE /me` only deactivates the user, who can undo it
+//! by logging in again. Once the grace period is over the user is purged,
+//! which takes their tweets, follows, sessions and password resets with them
+//! through `on delete cascade`. Their uploaded images are removed from the
+//! blob store afterwards.
+
+use crate::blobs::BlobStore;
+use crate::config::Config;
+use crate::endpoints::uploads::delete_renditions;
+use crate::images::ImageKind;
+use sqlx::{query, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often the background task looks for accounts to purge.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Removes a user and everything that belongs to them.
+pub async fn purge(
+    db_pool: &PgPool,
+    blobs: &dyn BlobStore,
+    user_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db_pool.begin().await?;
+    // Follow counts only drop when a user is deactivated, not when their
+    // follows are deleted along with them
+    query!(
+        "update users set deactivated_at = $1 where id = $2 and deactivated_at is null",
+        crate::clock::current_time().await,
+        user_id,
+    )
+    .execute(&mut tx)
+    .await?;
+    let purged = query!(
+        "delete from users where id = $1 returning avatar_key, header_key",
+        user_id,
+    )
+    .fetch_optional(&mut tx)
+    .await?;
+    tx.commit().await?;
+
+    if let Some(user) = purged {
+        delete_images(blobs, user.avatar_key, user.header_key).await;
+    }
+    Ok(())
+}
+
+/// Purges every account deactivated longer ago than the grace period and
+/// returns how many there were.
+pub async fn purge_expired(
+    db_pool: &PgPool,
+    config: &Config,
+    blobs: &dyn BlobStore,
+) -> Result<u64, sqlx::Error> {
+    let now = crate::clock::current_time().await;
+
+    let mut tx = db_pool.begin().await?;
+    let purged = query!(
+        r#"
+            delete from users
+            where deactivated_at <= $1
+            returning avatar_key, header_key
+        "#,
+        now - config.account_deletion_grace_period,
+    )
+    .fetch_all(&mut tx)
+    .await?;
+    tx.commit().await?;
+
+    let count = purged.len() as u64;
+    for user in purged {
+        delete_images(blobs, user.avatar_key, user.header_key).await;
+    }
+    Ok(count)
+}
+
+/// Only once the users are gone, so nothing still points at the files.
+async fn delete_images(
+    blobs: &dyn BlobStore,
+    avatar_key: Option<String>,
+    header_key: Option<String>,
+) {
+    if let Some(key) = avatar_key {
+        delete_renditions(blobs, ImageKind::Avatar, &key).await;
+    }
+    if let Some(key) = header_key {
+        delete_renditions(blobs, ImageKind::Header, &key).await;
+    }
+}
+
+/// Runs `purge_expired` every `PURGE_INTERVAL` for as long as the server is up.
+pub fn spawn_purger(db_pool: PgPool, config: Config, blobs: Arc<dyn BlobStore>) {
+    async_std::task::spawn(async move {
+        loop {
+            match purge_expired(&db_pool, &config, &*blobs).await {
+                Ok(0) => {}
+                Ok(purged) => log::info!("purged {} deleted accounts", purged),
+                Err(err) => log::error!("failed to purge deleted accounts: {}", err),
+            }
+            async_std::task::sleep(PURGE_INTERVAL).await;
+        }
+    });
+}
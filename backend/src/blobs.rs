@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BlobStoreError {
+    #[error("invalid blob key: {0:?}")]
+    InvalidKey(String),
+    #[error("failed to access blob: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct Blob {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+/// Where uploaded files live. Keys are `/` separated paths made of letters,
+/// digits, `_`, `-` and `.`, like S3 object keys, so a bucket-backed store
+/// can be added next to `LocalBlobStore` without changing callers.
+#[async_trait]
+pub trait BlobStore: Debug + Send + Sync {
+    async fn put(&self, key: &str, blob: Blob) -> Result<(), BlobStoreError>;
+
+    async fn get(&self, key: &str) -> Result<Option<Blob>, BlobStoreError>;
+
+    /// Does nothing if there's no such blob.
+    async fn delete(&self, key: &str) -> Result<(), BlobStoreError>;
+}
+
+/// Picks a store based on `BLOB_STORE`. Only `local` exists so far, which
+/// keeps files in `BLOB_DIR` (default `tmp/blobs`).
+pub fn from_env() -> Arc<dyn BlobStore> {
+    match std::env::var("BLOB_STORE").ok().as_deref() {
+        None | Some("local") => {
+            let dir = std::env::var("BLOB_DIR").unwrap_or_else(|_| "tmp/blobs".to_string());
+            Arc::new(LocalBlobStore::new(dir))
+        }
+        Some(other) => panic!("Unknown blob store `{}`", other),
+    }
+}
+
+/// Keeps every blob as a file under `dir`. The content type isn't stored, it
+/// comes from the key's extension.
+#[derive(Debug)]
+pub struct LocalBlobStore {
+    dir: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, BlobStoreError> {
+        if !is_valid_key(key) {
+            return Err(BlobStoreError::InvalidKey(key.to_string()));
+        }
+        Ok(key.split('/').fold(self.dir.clone(), |path, segment| path.join(segment)))
+    }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, blob: Blob) -> Result<(), BlobStoreError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            async_std::fs::create_dir_all(parent).await?;
+        }
+        async_std::fs::write(path, blob.bytes).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Blob>, BlobStoreError> {
+        let path = self.path(key)?;
+        match async_std::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(Blob {
+                bytes,
+                content_type: content_type_for(key).to_string(),
+            })),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), BlobStoreError> {
+        let path = self.path(key)?;
+        match async_std::fs::remove_file(path).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Also what stops keys from reaching outside the store, no segment can be
+/// `..` or empty.
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        })
+}
+
+pub fn content_type_for(key: &str) -> &'static str {
+    match key.rsplit('.').next() {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
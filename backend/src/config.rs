@@ -0,0 +1,97 @@
+use chrono::Duration;
+use std::str::FromStr;
+
+/// Tunables read from the environment at startup. Every value has a default
+/// so only the ones that differ from it need to be set.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// How long a login stays valid. `SESSION_TTL_DAYS`, default 30.
+    pub session_ttl: Duration,
+    /// How long a password reset link works. `PASSWORD_RESET_TTL_MINUTES`,
+    /// default 60.
+    pub password_reset_ttl: Duration,
+    /// Where the frontend lives, for links in emails. `APP_URL`, default
+    /// `http://localhost:8000`.
+    pub app_url: String,
+    /// Where this server can be reached from the outside, for links to
+    /// uploaded files. `PUBLIC_URL`, default `http://localhost:8080`.
+    pub public_url: String,
+    /// Failed logins for one username before it has to back off.
+    /// `LOGIN_BACKOFF_THRESHOLD`, default 5.
+    pub login_backoff_threshold: i64,
+    /// Same as `login_backoff_threshold`, but per client IP, where several
+    /// people may share an address. `LOGIN_IP_BACKOFF_THRESHOLD`, default 20.
+    pub login_ip_backoff_threshold: i64,
+    /// First backoff delay, doubled on every further failure.
+    /// `LOGIN_BACKOFF_BASE_SECONDS`, default 1.
+    pub login_backoff_base: Duration,
+    /// Longest a single backoff can get. `LOGIN_BACKOFF_MAX_MINUTES`,
+    /// default 15.
+    pub login_backoff_max: Duration,
+    /// Failed logins for one username before it's locked outright.
+    /// `LOGIN_LOCKOUT_THRESHOLD`, default 10.
+    pub login_lockout_threshold: i64,
+    /// How long a lock lasts. `LOGIN_LOCKOUT_MINUTES`, default 15.
+    pub login_lockout_duration: Duration,
+    /// Failures older than this are forgotten. `LOGIN_ATTEMPT_WINDOW_MINUTES`,
+    /// default 60.
+    pub login_attempt_window: Duration,
+    /// Take the client IP from `Forwarded`/`X-Forwarded-For` instead of the
+    /// socket. Only turn this on behind a proxy that sets those headers
+    /// itself. `TRUST_PROXY_HEADERS`, default false.
+    pub trust_proxy_headers: bool,
+    /// Answer signups for a claimed username with the same generic error as
+    /// a claimed email, instead of saying the username is taken.
+    /// `CONCEAL_CLAIMED_USERNAMES`, default false.
+    pub conceal_claimed_usernames: bool,
+    /// How long a deleted account can still be restored by logging in before
+    /// it's purged. Zero purges straight away. `ACCOUNT_DELETION_GRACE_DAYS`,
+    /// default 14.
+    pub account_deletion_grace_period: Duration,
+    /// How long after posting a tweet its author can still edit it.
+    /// `TWEET_EDIT_WINDOW_MINUTES`, default 30.
+    pub tweet_edit_window: Duration,
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        Self {
+            session_ttl: Duration::days(env_var("SESSION_TTL_DAYS").unwrap_or(30)),
+            password_reset_ttl: Duration::minutes(
+                env_var("PASSWORD_RESET_TTL_MINUTES").unwrap_or(60),
+            ),
+            app_url: env_var("APP_URL").unwrap_or_else(|| "http://localhost:8000".to_string()),
+            public_url: env_var("PUBLIC_URL")
+                .unwrap_or_else(|| "http://localhost:8080".to_string()),
+            login_backoff_threshold: env_var("LOGIN_BACKOFF_THRESHOLD").unwrap_or(5),
+            login_ip_backoff_threshold: env_var("LOGIN_IP_BACKOFF_THRESHOLD").unwrap_or(20),
+            login_backoff_base: Duration::seconds(
+                env_var("LOGIN_BACKOFF_BASE_SECONDS").unwrap_or(1),
+            ),
+            login_backoff_max: Duration::minutes(env_var("LOGIN_BACKOFF_MAX_MINUTES").unwrap_or(15)),
+            login_lockout_threshold: env_var("LOGIN_LOCKOUT_THRESHOLD").unwrap_or(10),
+            login_lockout_duration: Duration::minutes(
+                env_var("LOGIN_LOCKOUT_MINUTES").unwrap_or(15),
+            ),
+            login_attempt_window: Duration::minutes(
+                env_var("LOGIN_ATTEMPT_WINDOW_MINUTES").unwrap_or(60),
+            ),
+            trust_proxy_headers: env_var("TRUST_PROXY_HEADERS").unwrap_or(false),
+            conceal_claimed_usernames: env_var("CONCEAL_CLAIMED_USERNAMES").unwrap_or(false),
+            account_deletion_grace_period: Duration::days(
+                env_var("ACCOUNT_DELETION_GRACE_DAYS").unwrap_or(14),
+            ),
+            tweet_edit_window: Duration::minutes(
+                env_var("TWEET_EDIT_WINDOW_MINUTES").unwrap_or(30),
+            ),
+        }
+    }
+}
+
+fn env_var<T: FromStr>(key: &str) -> Option<T> {
+    let value = std::env::var(key).ok()?;
+    match value.parse() {
+        Ok(value) => Some(value),
+        Err(_) => panic!("Invalid value for `{}`: {:?}", key, value),
+    }
+}
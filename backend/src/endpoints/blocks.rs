@@ -0,0 +1,119 @@
+use crate::endpoints::authenticate;
+use crate::endpoints::users::find_user;
+use crate::errors::api_error;
+use crate::{BackendApiEndpoint, State};
+use async_trait::async_trait;
+use shared::errors::ErrorCode;
+use shared::{ApiEndpoint, Block, NoPayLoad, Unblock};
+use sqlx::{query, PgPool};
+use tide::{Request, StatusCode};
+use uuid::Uuid;
+
+#[async_trait]
+impl BackendApiEndpoint for Block {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let username = req.param::<String>("username")?;
+        // Blocking back someone who blocked you first is allowed, so their
+        // block doesn't hide them here
+        let blocked_id = find_user(db_pool, &username, None).await?.id;
+
+        if current_user.id == blocked_id {
+            return Err(api_error(
+                StatusCode::UnprocessableEntity,
+                ErrorCode::CannotBlockSelf,
+                "You cannot block yourself",
+            ));
+        }
+
+        let now = crate::clock::current_time().await;
+        let mut tx = db_pool.begin().await?;
+        let rows_inserted = query!(
+            r#"
+                insert into blocks (id, blocker_id, blocked_id, created_at, updated_at)
+                values ($1, $2, $3, $4, $5)
+                on conflict (blocker_id, blocked_id) do nothing
+            "#,
+            Uuid::new_v4(),
+            current_user.id,
+            blocked_id,
+            now,
+            now,
+        )
+        .execute(&mut tx)
+        .await?;
+        query!(
+            r#"
+                delete from follows
+                where (follower_id = $1 and followee_id = $2)
+                    or (follower_id = $2 and followee_id = $1)
+            "#,
+            current_user.id,
+            blocked_id,
+        )
+        .execute(&mut tx)
+        .await?;
+        query!(
+            r#"
+                delete from follow_requests
+                where (requester_id = $1 and target_id = $2)
+                    or (requester_id = $2 and target_id = $1)
+            "#,
+            current_user.id,
+            blocked_id,
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+
+        if rows_inserted == 1 {
+            Ok(((), StatusCode::Created))
+        } else {
+            Ok(((), StatusCode::Ok))
+        }
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for Unblock {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let username = req.param::<String>("username")?;
+        let blocked_id = find_user(db_pool, &username, None).await?.id;
+
+        // Follows ended by the block stay ended
+        query!(
+            "delete from blocks where blocker_id = $1 and blocked_id = $2",
+            current_user.id,
+            blocked_id,
+        )
+        .execute(db_pool)
+        .await?;
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+pub async fn has_blocked(
+    db_pool: &PgPool,
+    blocker_id: Uuid,
+    blocked_id: Uuid,
+) -> tide::Result<bool> {
+    let row = query!(
+        "select 1 as one from blocks where blocker_id = $1 and blocked_id = $2",
+        blocker_id,
+        blocked_id,
+    )
+    .fetch_optional(db_pool)
+    .await?;
+
+    Ok(row.is_some())
+}
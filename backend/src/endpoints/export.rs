@@ -0,0 +1,227 @@
+//! `GET /me/export`, a download of everything we keep about the current user
+//! in the shape of `shared::responses::ExportArchive`.
+//!
+//! The JSON is written piece by piece from database cursors into a channel
+//! that backs the response body, so no more than a few rows are held in
+//! memory however big the account is. The zip version needs the finished
+//! JSON to compress, so it goes through a temporary file instead, written
+//! off the async executor.
+
+use crate::endpoints::authenticate;
+use crate::endpoints::tweets::{TweetRow, TWEET_COLUMNS};
+use crate::endpoints::users::{UserRow, USER_COLUMNS};
+use crate::State;
+use async_std::io::BufReader;
+use futures::channel::mpsc;
+use futures::executor;
+use futures::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::responses::{TweetResponse, UserResponse};
+use shared::ExportFormat;
+use sqlx::PgPool;
+use std::io::{self, Seek, SeekFrom, Write};
+use thiserror::Error;
+use tide::{Body, Request, Response, StatusCode};
+use uuid::Uuid;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+/// How many chunks the archive writer can get ahead of the client.
+const CHUNK_BUFFER: usize = 16;
+
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+#[derive(Debug, Error)]
+enum ExportError {
+    #[error("database error: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("could not serialize export: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("client went away")]
+    Disconnected,
+}
+
+type Chunk = io::Result<Vec<u8>>;
+
+pub async fn export(req: Request<State>) -> tide::Result {
+    let user = authenticate(&req).await?;
+    let query = req.query::<ExportQuery>()?;
+    let db_pool = req.state().db_pool.clone();
+
+    let (sender, receiver) = mpsc::channel::<Chunk>(CHUNK_BUFFER);
+    let archive_user = user.clone();
+    async_std::task::spawn(async move {
+        let mut out = ArchiveWriter { chunks: sender };
+        if let Err(err) = write_archive(&db_pool, archive_user, &mut out).await {
+            log::error!("failed to export user data: {}", err);
+            // Ending the body with an error makes the download fail, rather
+            // than leaving the client with a truncated file that looks fine
+            out.abort(err).await;
+        }
+    });
+
+    let mut resp = Response::new(StatusCode::Ok);
+    let extension = match query.format {
+        ExportFormat::Json => {
+            let reader = BufReader::new(receiver.into_async_read());
+            resp.set_body(Body::from_reader(reader, None));
+            resp.set_content_type(tide::http::mime::JSON);
+            "json"
+        }
+        ExportFormat::Zip => {
+            let (file, len) = zip_archive(receiver).await?;
+            resp.set_body(Body::from_reader(BufReader::new(file), Some(len)));
+            resp.insert_header("Content-Type", "application/zip");
+            "zip"
+        }
+    };
+    resp.insert_header(
+        "Content-Disposition",
+        format!(
+            "attachment; filename=\"witter-{}-export.{}\"",
+            user.username, extension
+        ),
+    );
+
+    Ok(resp)
+}
+
+struct ArchiveWriter {
+    chunks: mpsc::Sender<Chunk>,
+}
+
+impl ArchiveWriter {
+    async fn write(&mut self, bytes: impl Into<Vec<u8>>) -> Result<(), ExportError> {
+        self.chunks
+            .send(Ok(bytes.into()))
+            .await
+            .map_err(|_| ExportError::Disconnected)
+    }
+
+    async fn write_json<T: Serialize>(&mut self, value: &T) -> Result<(), ExportError> {
+        let bytes = serde_json::to_vec(value)?;
+        self.write(bytes).await
+    }
+
+    /// Writes `"key":[...]` with one element per row, as they arrive.
+    async fn write_array<T, S>(&mut self, key: &str, mut rows: S) -> Result<(), ExportError>
+    where
+        T: Serialize,
+        S: Stream<Item = Result<T, sqlx::Error>> + Unpin,
+    {
+        self.write(format!("\"{}\":[", key)).await?;
+        let mut first = true;
+        while let Some(row) = rows.try_next().await? {
+            if !first {
+                self.write(",").await?;
+            }
+            first = false;
+            self.write_json(&row).await?;
+        }
+        self.write("]").await
+    }
+
+    async fn abort(&mut self, err: ExportError) {
+        let err = io::Error::new(io::ErrorKind::Other, err.to_string());
+        self.chunks.send(Err(err)).await.ok();
+    }
+}
+
+async fn write_archive(
+    db_pool: &PgPool,
+    user: UserResponse,
+    out: &mut ArchiveWriter,
+) -> Result<(), ExportError> {
+    let user_id = user.id;
+    let now = crate::clock::current_time().await;
+
+    out.write("{\"user\":").await?;
+    out.write_json(&user).await?;
+    out.write(",\"exported_at\":").await?;
+    out.write_json(&now).await?;
+    out.write(",").await?;
+
+    // Nobody is viewing these, so `liked_by_me` is left null
+    let tweets_sql = format!(
+        r#"
+            select {}
+            from tweets
+            inner join users on users.id = tweets.user_id
+            where tweets.user_id = $1
+            order by tweets.created_at
+        "#,
+        TWEET_COLUMNS
+    );
+    let tweets = sqlx::query_as::<_, TweetRow>(&tweets_sql)
+        .bind(user_id)
+        .bind(None::<Uuid>)
+        .fetch(db_pool)
+        .map_ok(TweetResponse::from);
+    out.write_array("tweets", tweets).await?;
+    out.write(",").await?;
+
+    let following_sql = format!(
+        r#"
+            select {}
+            from users
+            inner join follows on
+                follows.follower_id = $1
+                and follows.followee_id = users.id
+            order by follows.created_at
+        "#,
+        USER_COLUMNS
+    );
+    let following = sqlx::query_as::<_, UserRow>(&following_sql)
+        .bind(user_id)
+        .fetch(db_pool)
+        .map_ok(UserResponse::from);
+    out.write_array("following", following).await?;
+    out.write(",").await?;
+
+    let followers_sql = format!(
+        r#"
+            select {}
+            from users
+            inner join follows on
+                follows.followee_id = $1
+                and follows.follower_id = users.id
+            order by follows.created_at
+        "#,
+        USER_COLUMNS
+    );
+    let followers = sqlx::query_as::<_, UserRow>(&followers_sql)
+        .bind(user_id)
+        .fetch(db_pool)
+        .map_ok(UserResponse::from);
+    out.write_array("followers", followers).await?;
+
+    out.write("}").await
+}
+
+/// Compresses the JSON into `export.json` inside a zip in a temporary file,
+/// which is removed once the response is done with it.
+///
+/// Deflating and the file writes block, so they run on the blocking pool and
+/// pull chunks off the channel from there.
+async fn zip_archive(chunks: mpsc::Receiver<Chunk>) -> io::Result<(async_std::fs::File, usize)> {
+    let (file, len) = async_std::task::spawn_blocking(move || -> io::Result<_> {
+        let mut zip = ZipWriter::new(tempfile::tempfile()?);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file("export.json", options)?;
+
+        for chunk in executor::block_on_stream(chunks) {
+            zip.write_all(&chunk?)?;
+        }
+
+        let mut file = zip.finish()?;
+        let len = file.seek(SeekFrom::Current(0))?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok((file, len))
+    })
+    .await?;
+
+    Ok((file.into(), len as usize))
+}
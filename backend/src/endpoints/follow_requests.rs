@@ -0,0 +1,136 @@
+use crate::endpoints::authenticate;
+use crate::endpoints::users::{UserRow, USER_COLUMNS};
+use crate::errors::api_error;
+use crate::{BackendApiEndpoint, State};
+use async_trait::async_trait;
+use shared::errors::ErrorCode;
+use shared::responses::FollowRequestResponse;
+use shared::{ApiEndpoint, ApproveFollowRequest, FollowRequests, NoPayLoad, RejectFollowRequest};
+use sqlx::{query, FromRow, Row};
+use tide::{Request, StatusCode};
+use uuid::Uuid;
+
+#[async_trait]
+impl BackendApiEndpoint for FollowRequests {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+
+        let sql = format!(
+            r#"
+                select
+                    follow_requests.id as request_id,
+                    follow_requests.created_at as requested_at,
+                    {}
+                from follow_requests
+                inner join users on users.id = follow_requests.requester_id
+                where follow_requests.target_id = $1
+                    and users.deactivated_at is null
+                order by follow_requests.created_at
+            "#,
+            USER_COLUMNS
+        );
+        let requests = sqlx::query(&sql)
+            .bind(current_user.id)
+            .fetch_all(db_pool)
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(FollowRequestResponse {
+                    id: row.try_get("request_id")?,
+                    created_at: row.try_get("requested_at")?,
+                    user: UserRow::from_row(row)?.into(),
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+        Ok((requests, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for ApproveFollowRequest {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let id = request_id(&req)?;
+        let now = crate::clock::current_time().await;
+
+        let mut tx = db_pool.begin().await?;
+        let request = query!(
+            r#"
+                delete from follow_requests
+                where id = $1 and target_id = $2
+                returning requester_id
+            "#,
+            id,
+            current_user.id,
+        )
+        .fetch_optional(&mut tx)
+        .await?
+        .ok_or_else(follow_request_not_found)?;
+
+        query!(
+            r#"
+                insert into follows (id, follower_id, followee_id, created_at, updated_at)
+                values ($1, $2, $3, $4, $5)
+                on conflict (follower_id, followee_id) do nothing
+            "#,
+            Uuid::new_v4(),
+            request.requester_id,
+            current_user.id,
+            now,
+            now,
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for RejectFollowRequest {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let id = request_id(&req)?;
+
+        let rows_deleted = query!(
+            "delete from follow_requests where id = $1 and target_id = $2",
+            id,
+            current_user.id,
+        )
+        .execute(db_pool)
+        .await?;
+
+        if rows_deleted == 0 {
+            return Err(follow_request_not_found());
+        }
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+/// Ids that don't parse can't exist either, so they're a 404 too.
+fn request_id(req: &Request<State>) -> tide::Result<Uuid> {
+    req.param::<Uuid>("id").map_err(|_| follow_request_not_found())
+}
+
+fn follow_request_not_found() -> tide::Error {
+    api_error(
+        StatusCode::NotFound,
+        ErrorCode::FollowRequestNotFound,
+        "Follow request not found",
+    )
+}
@@ -1,14 +1,26 @@
-use crate::endpoints::authenticate;
-use crate::responses::BuildApiResponse;
+use crate::endpoints::tweets::{
+    embed_quoted_tweets, load_tweets_by_id, VISIBLE_AUTHOR, VISIBLE_IF_PROTECTED,
+};
+use crate::endpoints::uploads::delete_renditions;
+use crate::endpoints::users::load_users_by_id;
+use crate::endpoints::{authenticate, authenticate_session, Pagination};
+use crate::accounts;
+use crate::errors::api_error;
+use crate::images::ImageKind;
+use crate::passwords;
+use crate::validation::{validate_profile, FieldErrors};
 use crate::BackendApiEndpoint;
 use crate::State;
 use async_trait::async_trait;
-use serde::Deserialize;
 use shared::{
-    responses::{UserResponse, TweetResponse}, 
-    ApiEndpoint, Me, NoPayLoad, Timeline};
-use sqlx::{query_as, query};
+    errors::ErrorCode,
+    responses::{AccountDeletionResponse, UserResponse, TweetResponse, SessionResponse}, 
+    payloads::{ChangePasswordPayload, DeleteAccountPayload, UpdateProfilePayload},
+    ApiEndpoint, ChangePassword, DeleteAccount, Me, NoPayLoad, RevokeSession, Sessions, Timeline,
+    UpdateProfile};
+use sqlx::query;
 use tide::{StatusCode, Request};
+use uuid::Uuid;
 
 #[async_trait]
 impl BackendApiEndpoint for Me {
@@ -21,10 +33,119 @@ impl BackendApiEndpoint for Me {
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct Pagination {
-    page: Option<usize>,
-    page_size: Option<usize>,
+#[async_trait]
+impl BackendApiEndpoint for UpdateProfile {
+    async fn handler(
+        req: Request<State>,
+        payload: UpdateProfilePayload,
+    ) -> tide::Result<(UserResponse, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let user = authenticate(&req).await?;
+
+        let mut errors = FieldErrors::new();
+        validate_profile(&payload, &mut errors);
+        errors.into_result()?;
+
+        // A thumbnail only exists for uploaded avatars, so it goes stale as
+        // soon as the avatar is pointed somewhere else, as do the files
+        let replaces_avatar = payload.avatar_url.is_some();
+        let avatar_thumbnail_url = if replaces_avatar {
+            None
+        } else {
+            user.avatar_thumbnail_url.clone()
+        };
+
+        let user = UserResponse {
+            display_name: merge_profile_field(user.display_name, payload.display_name),
+            bio: merge_profile_field(user.bio, payload.bio),
+            location: merge_profile_field(user.location, payload.location),
+            website: merge_profile_field(user.website, payload.website),
+            avatar_url: merge_profile_field(user.avatar_url, payload.avatar_url),
+            avatar_thumbnail_url,
+            protected: payload.protected.unwrap_or(user.protected),
+            ..user
+        };
+        let now = crate::clock::current_time().await;
+
+        let mut tx = db_pool.begin().await?;
+        let replaced_avatar_key = if replaces_avatar {
+            query!("select avatar_key from users where id = $1 for update", user.id)
+                .fetch_one(&mut tx)
+                .await?
+                .avatar_key
+        } else {
+            None
+        };
+        query!(
+            r#"
+                update users
+                set display_name = $1,
+                    bio = $2,
+                    location = $3,
+                    website = $4,
+                    avatar_url = $5,
+                    avatar_thumbnail_url = $6,
+                    avatar_key = case when $10 then null else avatar_key end,
+                    protected = $7,
+                    updated_at = $8
+                where id = $9
+            "#,
+            user.display_name,
+            user.bio,
+            user.location,
+            user.website,
+            user.avatar_url,
+            user.avatar_thumbnail_url,
+            user.protected,
+            now,
+            user.id,
+            replaces_avatar,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        // Nothing left to approve once anyone can follow
+        if !user.protected {
+            query!(
+                r#"
+                    insert into follows (id, follower_id, followee_id, created_at, updated_at)
+                    select gen_random_uuid(), requester_id, target_id, $1, $1
+                    from follow_requests
+                    where target_id = $2
+                    on conflict (follower_id, followee_id) do nothing
+                "#,
+                now,
+                user.id,
+            )
+            .execute(&mut tx)
+            .await?;
+            query!("delete from follow_requests where target_id = $1", user.id)
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        if let Some(key) = replaced_avatar_key {
+            delete_renditions(&*req.state().blobs, ImageKind::Avatar, &key).await;
+        }
+
+        Ok((user, StatusCode::Ok))
+    }
+}
+
+/// Leaves a field alone if it wasn't sent and clears it if it was sent blank.
+fn merge_profile_field(current: Option<String>, update: Option<String>) -> Option<String> {
+    match update {
+        None => current,
+        Some(value) => Some(value.trim().to_string()).filter(|value| !value.is_empty()),
+    }
+}
+
+/// One entry in the timeline, before its tweet is loaded.
+#[derive(sqlx::FromRow)]
+struct FeedItem {
+    id: Uuid,
+    retweeter_id: Option<Uuid>,
 }
 
 #[async_trait]
@@ -36,62 +157,329 @@ impl BackendApiEndpoint for Timeline {
         let db_pool = &req.state().db_pool;
 
         let pagination = req.query::<Pagination>()?;
-        let page_size = pagination.page_size.unwrap_or(20) as i64;
-        
-        let page = pagination.page.unwrap_or(1) as i64;
-        let offset = (page - 1) * page_size;
-        
 
         let current_user = authenticate(&req).await?;
     
-        let tweets = query!(
+        // Retweets of tweets the viewer would see anyway are left out, and a
+        // tweet retweeted by several people shows up once, for the latest.
+        // The feed only carries ids and the tweets themselves are loaded
+        // afterwards.
+        let sql = format!(
             r#"
-            select 
-                tweets.id as tweet_id
-                , tweets.text  as tweet_text
-                , tweets.created_at as tweet_created_at
-                , users.id as user_id
-                , users.username as user_username
-            from (
-                select id, text, created_at, user_id
-                from tweets 
-                where user_id = $1
+            with feed as (
+                select
+                    tweets.id as tweet_id
+                    , tweets.created_at as feed_at
+                    , null::uuid as retweeter_id
+                from (
+                    select id, created_at, user_id, in_reply_to_id
+                    from tweets
+                    where user_id = $2
+
+                    union all
 
+                    select tweets.id, tweets.created_at, tweets.user_id, tweets.in_reply_to_id
+                    from users
+                    inner join follows on
+                        follows.follower_id = $2
+                        and follows.followee_id = users.id
+                    inner join tweets on
+                        tweets.user_id = users.id
+                ) tweets
+                -- Replies only show up when the viewer follows whoever they're
+                -- replying to as well
+                where (
+                    tweets.in_reply_to_id is null
+                    or tweets.user_id = $2
+                    or exists (
+                        select 1 from tweets as parents
+                        where parents.id = tweets.in_reply_to_id
+                            and (
+                                parents.user_id = $2
+                                or exists (
+                                    select 1 from follows
+                                    where follows.follower_id = $2
+                                        and follows.followee_id = parents.user_id
+                                )
+                            )
+                    )
+                )
                 union all
-                
-                select tweets.id, tweets.text, tweets.created_at, tweets.user_id
-                from users
-                inner join follows on
-                    follows.follower_id = $1
-                    and follows.followee_id = users.id 
-                inner join tweets on 
-                    tweets.user_id = users.id
-            ) tweets
+                select * from (
+                    select distinct on (retweets.tweet_id)
+                        retweets.tweet_id
+                        , retweets.created_at as feed_at
+                        , retweets.user_id as retweeter_id
+                    from retweets
+                    inner join tweets on tweets.id = retweets.tweet_id
+                    inner join users as retweeters on retweeters.id = retweets.user_id
+                    where (
+                            retweets.user_id = $2
+                            or exists (
+                                select 1 from follows
+                                where follows.follower_id = $2
+                                    and follows.followee_id = retweets.user_id
+                            )
+                        )
+                        and tweets.user_id <> $2
+                        and not exists (
+                            select 1 from follows
+                            where follows.follower_id = $2 and follows.followee_id = tweets.user_id
+                        )
+                        and retweeters.deactivated_at is null
+                        and not exists (
+                            select 1 from blocks
+                            where (blocks.blocker_id = $2 and blocks.blocked_id = retweeters.id)
+                                or (blocks.blocker_id = retweeters.id and blocks.blocked_id = $2)
+                        )
+                        and not exists (
+                            select 1 from muted_users
+                            where muted_users.user_id = $2
+                                and muted_users.muted_user_id = retweeters.id
+                                and (muted_users.expires_at is null or muted_users.expires_at > $1)
+                        )
+                    order by retweets.tweet_id, retweets.created_at desc
+                ) as latest_retweets
+            )
+            select tweets.id, feed.retweeter_id
+            from feed
+            inner join tweets on tweets.id = feed.tweet_id
             inner join users on users.id = tweets.user_id
-            order by tweets.created_at desc
-            limit $2
-            offset $3
-        "#,
-        current_user.id,
-        page_size,
-        offset
+            where {}
+                and {}
+                and not exists (
+                    select 1 from muted_users
+                    where muted_users.user_id = $2
+                        and muted_users.muted_user_id = users.id
+                        and (muted_users.expires_at is null or muted_users.expires_at > $1)
+                )
+                and (
+                    users.id = $2
+                    or not exists (
+                        select 1 from muted_keywords
+                        where muted_keywords.user_id = $2
+                            and (muted_keywords.expires_at is null or muted_keywords.expires_at > $1)
+                            and strpos(lower(tweets.text), lower(muted_keywords.keyword)) > 0
+                    )
+                )
+            order by feed.feed_at desc
+            limit $3
+            offset $4
+            "#,
+            VISIBLE_AUTHOR,
+            VISIBLE_IF_PROTECTED
+        );
+        let feed = sqlx::query_as::<_, FeedItem>(&sql)
+            .bind(crate::clock::current_time().await)
+            .bind(current_user.id)
+            .bind(pagination.limit())
+            .bind(pagination.offset())
+            .fetch_all(db_pool)
+            .await?;
+
+        let ids = feed.iter().map(|item| item.id).collect::<Vec<_>>();
+        let mut tweets = load_tweets_by_id(db_pool, &ids, Some(current_user.id)).await?;
+
+        let retweeter_ids = feed
+            .iter()
+            .filter_map(|item| item.retweeter_id)
+            .collect::<Vec<_>>();
+        let retweeters = load_users_by_id(db_pool, &retweeter_ids).await?;
+
+        let mut tweet_responses = feed
+            .into_iter()
+            .filter_map(|item| {
+                let tweet = tweets.remove(&item.id)?;
+                Some(TweetResponse {
+                    retweeted_by: item
+                        .retweeter_id
+                        .and_then(|id| retweeters.get(&id))
+                        .cloned(),
+                    ..tweet
+                })
+            })
+            .collect::<Vec<_>>();
+
+        embed_quoted_tweets(db_pool, &mut tweet_responses, Some(current_user.id)).await?;
+
+        Ok((tweet_responses, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for Sessions {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let session = authenticate_session(&req).await?;
+        let now = crate::clock::current_time().await;
+
+        let rows = query!(
+            r#"
+                select id, user_agent, ip_address, created_at, last_used_at, expires_at
+                from auth_tokens
+                where user_id = $1 and expires_at > $2
+                order by last_used_at desc
+            "#,
+            session.user.id,
+            now,
         )
         .fetch_all(db_pool)
         .await?;
 
-        let tweet_responses = tweets
+        let sessions = rows
             .into_iter()
-            .map(|tweet| TweetResponse {
-                id: tweet.tweet_id.unwrap(),
-                text: tweet.tweet_text.unwrap(),
-                created_at: tweet.tweet_created_at.unwrap(),
-                user: UserResponse {
-                    id: tweet.user_id,
-                    username: tweet.user_username,
-                },
+            .map(|row| SessionResponse {
+                id: row.id,
+                user_agent: row.user_agent,
+                ip_address: row.ip_address,
+                created_at: row.created_at,
+                last_used_at: row.last_used_at,
+                expires_at: row.expires_at,
+                current: row.id == session.id,
             })
             .collect::<Vec<_>>();
 
-        Ok((tweet_responses, StatusCode::Ok))
+        Ok((sessions, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for RevokeSession {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let user = authenticate(&req).await?;
+        let session_id = req
+            .param::<Uuid>("id")
+            .map_err(|_| session_not_found())?;
+
+        let rows_deleted = query!(
+            "delete from auth_tokens where id = $1 and user_id = $2",
+            session_id,
+            user.id,
+        )
+        .execute(db_pool)
+        .await?;
+
+        if rows_deleted == 0 {
+            return Err(session_not_found());
+        }
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+fn session_not_found() -> tide::Error {
+    api_error(StatusCode::NotFound, ErrorCode::SessionNotFound, "Session not found")
+}
+
+#[async_trait]
+impl BackendApiEndpoint for ChangePassword {
+    async fn handler(
+        req: Request<State>,
+        payload: ChangePasswordPayload,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let session = authenticate_session(&req).await?;
+
+        let mut errors = FieldErrors::new();
+        if payload.new_password.is_empty() {
+            errors.add("new_password", "can't be blank");
+        }
+        errors.into_result()?;
+
+        let user = query!(
+            "select hashed_password from users where id = $1",
+            session.user.id,
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        if !passwords::verify(&user.hashed_password, &payload.current_password).await? {
+            return Err(api_error(
+                StatusCode::Forbidden,
+                ErrorCode::IncorrectPassword,
+                "Current password is incorrect",
+            ));
+        }
+
+        let hashed_password = passwords::hash(&payload.new_password).await?;
+        let now = crate::clock::current_time().await;
+
+        let mut tx = db_pool.begin().await?;
+        query!(
+            "update users set hashed_password = $1, updated_at = $2 where id = $3",
+            hashed_password,
+            now,
+            session.user.id,
+        )
+        .execute(&mut tx)
+        .await?;
+        query!(
+            "delete from auth_tokens where user_id = $1 and id <> $2",
+            session.user.id,
+            session.id,
+        )
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for DeleteAccount {
+    async fn handler(
+        req: Request<State>,
+        payload: DeleteAccountPayload,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let grace_period = req.state().config.account_deletion_grace_period;
+        let current_user = authenticate(&req).await?;
+
+        let user = query!(
+            "select hashed_password from users where id = $1",
+            current_user.id,
+        )
+        .fetch_one(db_pool)
+        .await?;
+
+        if !passwords::verify(&user.hashed_password, &payload.password).await? {
+            return Err(api_error(
+                StatusCode::Forbidden,
+                ErrorCode::IncorrectPassword,
+                "Password is incorrect",
+            ));
+        }
+
+        if grace_period <= chrono::Duration::zero() {
+            accounts::purge(db_pool, &*req.state().blobs, current_user.id).await?;
+            return Ok((AccountDeletionResponse { purge_at: None }, StatusCode::Ok));
+        }
+
+        let now = crate::clock::current_time().await;
+        let mut tx = db_pool.begin().await?;
+        query!(
+            "update users set deactivated_at = $1, updated_at = $1 where id = $2",
+            now,
+            current_user.id,
+        )
+        .execute(&mut tx)
+        .await?;
+        query!("delete from auth_tokens where user_id = $1", current_user.id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+
+        let resp = AccountDeletionResponse {
+            purge_at: Some(now + grace_period),
+        };
+        Ok((resp, StatusCode::Accepted))
     }
 }
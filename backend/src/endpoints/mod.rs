@@ -1,16 +1,28 @@
-use crate::{responses::BuildApiResponse, State};
+use crate::endpoints::users::{UserRow, USER_COLUMNS};
+use crate::errors::api_error;
+use crate::{tokens, State};
 use lazy_static::lazy_static;
 use regex::Regex;
-use serde_json::Value;
+use serde::Deserialize;
+use shared::errors::ErrorCode;
 use shared::responses::UserResponse;
-use sqlx::query_as;
+use sqlx::{query, FromRow, PgPool, Row};
+use std::convert::TryFrom;
+use std::net::SocketAddr;
 use tide::http::headers::HeaderName;
 use tide::http::Error;
 use tide::http::StatusCode;
-use tide::{Request, Response};
+use tide::Request;
+use uuid::Uuid;
 
+pub mod blocks;
+pub mod export;
+pub mod follow_requests;
 pub mod me;
+pub mod mutes;
+pub mod password_resets;
 pub mod tweets;
+pub mod uploads;
 pub mod users;
 
 lazy_static! {
@@ -18,34 +30,126 @@ lazy_static! {
 }
 
 pub async fn authenticate(req: &Request<State>) -> Result<UserResponse, Error> {
-    let auth_token = get_auth_token(req)?;
+    Ok(authenticate_session(req).await?.user)
+}
+
+/// Like `authenticate`, but `None` for requests without an `Authorization`
+/// header, for endpoints that also serve signed out visitors. A header with
+/// a bad token is still an error.
+pub async fn authenticate_if_present(req: &Request<State>) -> Result<Option<UserResponse>, Error> {
+    if optional_header("Authorization", req).is_none() {
+        return Ok(None);
+    }
+    authenticate(req).await.map(Some)
+}
+
+/// The user a request was made by, along with the id of the session their
+/// token belongs to.
+#[derive(Debug)]
+pub struct Session {
+    pub id: Uuid,
+    pub user: UserResponse,
+}
+
+pub async fn authenticate_session(req: &Request<State>) -> Result<Session, Error> {
+    let token_hash = tokens::hash(get_auth_token(req)?)?;
 
     let db_pool = &req.state().db_pool;
-    let user = query_as!(
-        UserResponse,
+    let now = crate::clock::current_time().await;
+    let sql = format!(
         r#"
-            select users.id, users.username
+            select auth_tokens.id as session_id, {}
             from users
             inner join auth_tokens
                 on auth_tokens.user_id = users.id
-                and auth_tokens.token = $1
-            "#,
-        auth_token
+                and auth_tokens.token_hash = $1
+                and auth_tokens.expires_at > $2
+        "#,
+        USER_COLUMNS
+    );
+    let row = sqlx::query(&sql)
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(|| {
+            api_error(StatusCode::Unauthorized, ErrorCode::InvalidToken, "Invalid auth token")
+        })?;
+    let session_id: Uuid = row.try_get("session_id")?;
+    let user: UserResponse = UserRow::from_row(&row)?.into();
+
+    query!(
+        "update auth_tokens set last_used_at = $1 where id = $2",
+        now,
+        session_id,
     )
-    .fetch_optional(db_pool)
+    .execute(db_pool)
+    .await?;
+
+    Ok(Session { id: session_id, user })
+}
+
+/// Starts a session for `user_id` on the device that made `req`, returning its token.
+pub async fn create_session(req: &Request<State>, user_id: Uuid) -> Result<String, Error> {
+    let db_pool = &req.state().db_pool;
+    let now = crate::clock::current_time().await;
+    let expires_at = now + req.state().config.session_ttl;
+
+    query!(
+        "delete from auth_tokens where user_id = $1 and expires_at <= $2",
+        user_id,
+        now,
+    )
+    .execute(db_pool)
+    .await?;
+
+    let raw_token = tokens::generate();
+    query!(
+        r#"
+            insert into auth_tokens (
+                id,
+                user_id,
+                token_hash,
+                user_agent,
+                ip_address,
+                expires_at,
+                last_used_at,
+                created_at,
+                updated_at
+            )
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        tokens::hash(&raw_token)?,
+        optional_header("User-Agent", req),
+        client_ip(req),
+        expires_at,
+        now,
+        now,
+        now,
+    )
+    .execute(db_pool)
     .await?;
-    
-    user.ok_or_else(|| Error::from_str(StatusCode::Unauthorized, "Invalid auth token"))
+
+    Ok(raw_token)
 }
 
 pub fn get_auth_token(req: &Request<State>) -> Result<&str, Error> {
-    let header_value = get_header("Authorization", req)?;
+    let header_value = optional_header("Authorization", req).ok_or_else(|| {
+        api_error(
+            StatusCode::BadRequest,
+            ErrorCode::MissingAuthToken,
+            "Missing value for `Authorization` header",
+        )
+    })?;
 
     let caps = match BEARER_TOKEN_REGEX.captures(header_value) {
         Some(caps) => caps,
         None => {
-            return Err(Error::from_str(
+            return Err(api_error(
                 StatusCode::BadRequest,
+                ErrorCode::InvalidAuthHeader,
                 "Unable to parse Authorization header value",
             ))
         }
@@ -54,29 +158,60 @@ pub fn get_auth_token(req: &Request<State>) -> Result<&str, Error> {
     Ok(caps.get(1).expect("missing capture group").as_str())
 }
 
-fn get_header<'a>(header_key: &str, req: &'a Request<State>) -> Result<&'a str, Error> {
-    let auth_header_key: HeaderName = header_key.parse()?;
-
-    let header_value = (|| {
-        let value = req.header(&auth_header_key)?.get(0)?;
-        Some(value.as_str())
-    })();
+fn optional_header<'a>(header_key: &str, req: &'a Request<State>) -> Option<&'a str> {
+    let header_key: HeaderName = header_key.parse().ok()?;
+    let value = req.header(&header_key)?.get(0)?;
+    Some(value.as_str())
+}
 
-    match header_value {
-        Some(value) => Ok(value),
-        None => {
-            return Err(Error::from_str(
-                StatusCode::BadRequest,
-                format!("Missing value for `{}` header", header_key),
-            ))
-        }
+/// The address the request came from, without the port. That's the socket
+/// peer unless the config says a proxy in front of us can be trusted to set
+/// `Forwarded`/`X-Forwarded-For`, since anyone can send those headers.
+pub fn client_ip(req: &Request<State>) -> Option<String> {
+    let address = if req.state().config.trust_proxy_headers {
+        req.remote()?
+    } else {
+        req.peer_addr()?
+    };
+    match address.parse::<SocketAddr>() {
+        Ok(socket_addr) => Some(socket_addr.ip().to_string()),
+        Err(_) => Some(address.to_string()),
     }
 }
 
-pub fn empty_response() -> Result<Response, Error> {
-    Value::Null.to_response()
+pub async fn is_following(
+    db_pool: &PgPool,
+    follower_id: Uuid,
+    followee_id: Uuid,
+) -> Result<bool, Error> {
+    let row = query!(
+        "select 1 as one from follows where follower_id = $1 and followee_id = $2",
+        follower_id,
+        followee_id,
+    )
+    .fetch_optional(db_pool)
+    .await?;
+
+    Ok(row.is_some())
+}
+
+/// `?page=` and `?page_size=` for listings, 20 per page by default and never
+/// more than 100.
+#[derive(Debug, Deserialize)]
+pub struct Pagination {
+    page: Option<usize>,
+    page_size: Option<usize>,
 }
 
-pub fn something_went_wrong(status_code: StatusCode) -> tide::Error {
-    tide::Error::from_str(status_code, "Something went wrong")
-}
\ No newline at end of file
+impl Pagination {
+    pub fn limit(&self) -> i64 {
+        self.page_size.unwrap_or(20).max(1).min(100) as i64
+    }
+
+    /// Saturates instead of overflowing, a page that far out is just empty.
+    pub fn offset(&self) -> i64 {
+        let pages_before = self.page.unwrap_or(1).max(1) - 1;
+        let offset = pages_before.saturating_mul(self.limit() as usize);
+        i64::try_from(offset).unwrap_or(i64::MAX)
+    }
+}
@@ -0,0 +1,318 @@
+use crate::endpoints::authenticate;
+use crate::endpoints::users::{find_user, load_user, UserRow, USER_COLUMNS};
+use crate::errors::api_error;
+use crate::validation::{validate_expires_at, validate_muted_keyword, FieldErrors};
+use crate::{BackendApiEndpoint, State};
+use async_trait::async_trait;
+use shared::errors::ErrorCode;
+use shared::payloads::{MuteKeywordPayload, MuteUserPayload};
+use shared::responses::{MutedKeywordResponse, MutedUserResponse};
+use shared::{
+    ApiEndpoint, MuteKeyword, MuteUser, MutedKeywords, MutedUsers, NoPayLoad, UnmuteKeyword,
+    UnmuteUser,
+};
+use sqlx::{query, FromRow, Row};
+use tide::{Request, StatusCode};
+use uuid::Uuid;
+
+#[async_trait]
+impl BackendApiEndpoint for MuteUser {
+    async fn handler(
+        req: Request<State>,
+        payload: MuteUserPayload,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let username = req.param::<String>("username")?;
+        let user = load_user(db_pool, &username, Some(current_user.id)).await?;
+
+        if current_user.id == user.id {
+            return Err(api_error(
+                StatusCode::UnprocessableEntity,
+                ErrorCode::CannotMuteSelf,
+                "You cannot mute yourself",
+            ));
+        }
+
+        let now = crate::clock::current_time().await;
+        let mut errors = FieldErrors::new();
+        validate_expires_at(payload.expires_at, now, &mut errors);
+        errors.into_result()?;
+
+        let mut tx = db_pool.begin().await?;
+        // An expired mute counts as no mute at all
+        query!(
+            r#"
+                delete from muted_users
+                where user_id = $1 and muted_user_id = $2 and expires_at <= $3
+            "#,
+            current_user.id,
+            user.id,
+            now,
+        )
+        .execute(&mut tx)
+        .await?;
+        let already_muted = query!(
+            "select 1 as one from muted_users where user_id = $1 and muted_user_id = $2",
+            current_user.id,
+            user.id,
+        )
+        .fetch_optional(&mut tx)
+        .await?
+        .is_some();
+        let row = query!(
+            r#"
+                insert into muted_users (
+                    id,
+                    user_id,
+                    muted_user_id,
+                    expires_at,
+                    created_at,
+                    updated_at
+                )
+                values ($1, $2, $3, $4, $5, $6)
+                on conflict (user_id, muted_user_id) do update
+                set expires_at = excluded.expires_at, updated_at = excluded.updated_at
+                returning expires_at, created_at
+            "#,
+            Uuid::new_v4(),
+            current_user.id,
+            user.id,
+            payload.expires_at,
+            now,
+            now,
+        )
+        .fetch_one(&mut tx)
+        .await?;
+        tx.commit().await?;
+
+        let status = if already_muted {
+            StatusCode::Ok
+        } else {
+            StatusCode::Created
+        };
+        Ok((
+            MutedUserResponse {
+                user,
+                expires_at: row.expires_at,
+                created_at: row.created_at,
+            },
+            status,
+        ))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for UnmuteUser {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let username = req.param::<String>("username")?;
+        let muted_user_id = find_user(db_pool, &username, None).await?.id;
+
+        query!(
+            "delete from muted_users where user_id = $1 and muted_user_id = $2",
+            current_user.id,
+            muted_user_id,
+        )
+        .execute(db_pool)
+        .await?;
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for MutedUsers {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let now = crate::clock::current_time().await;
+
+        let sql = format!(
+            r#"
+                select
+                    muted_users.expires_at,
+                    muted_users.created_at as muted_at,
+                    {}
+                from muted_users
+                inner join users on users.id = muted_users.muted_user_id
+                where muted_users.user_id = $1
+                    and (muted_users.expires_at is null or muted_users.expires_at > $2)
+                    and users.deactivated_at is null
+                order by muted_users.created_at desc
+            "#,
+            USER_COLUMNS
+        );
+        let mutes = sqlx::query(&sql)
+            .bind(current_user.id)
+            .bind(now)
+            .fetch_all(db_pool)
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(MutedUserResponse {
+                    expires_at: row.try_get("expires_at")?,
+                    created_at: row.try_get("muted_at")?,
+                    user: UserRow::from_row(row)?.into(),
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+        Ok((mutes, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for MuteKeyword {
+    async fn handler(
+        req: Request<State>,
+        payload: MuteKeywordPayload,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let keyword = payload.keyword.trim();
+        let now = crate::clock::current_time().await;
+
+        let mut errors = FieldErrors::new();
+        validate_muted_keyword(keyword, &mut errors);
+        validate_expires_at(payload.expires_at, now, &mut errors);
+        errors.into_result()?;
+
+        let mut tx = db_pool.begin().await?;
+        query!(
+            r#"
+                delete from muted_keywords
+                where user_id = $1 and lower(keyword) = lower($2) and expires_at <= $3
+            "#,
+            current_user.id,
+            keyword,
+            now,
+        )
+        .execute(&mut tx)
+        .await?;
+        let already_muted = query!(
+            r#"
+                select 1 as one from muted_keywords
+                where user_id = $1 and lower(keyword) = lower($2)
+            "#,
+            current_user.id,
+            keyword,
+        )
+        .fetch_optional(&mut tx)
+        .await?
+        .is_some();
+        let row = query!(
+            r#"
+                insert into muted_keywords (
+                    id,
+                    user_id,
+                    keyword,
+                    expires_at,
+                    created_at,
+                    updated_at
+                )
+                values ($1, $2, $3, $4, $5, $6)
+                on conflict (user_id, lower(keyword)) do update
+                set expires_at = excluded.expires_at, updated_at = excluded.updated_at
+                returning id, keyword, expires_at, created_at
+            "#,
+            Uuid::new_v4(),
+            current_user.id,
+            keyword,
+            payload.expires_at,
+            now,
+            now,
+        )
+        .fetch_one(&mut tx)
+        .await?;
+        tx.commit().await?;
+
+        let status = if already_muted {
+            StatusCode::Ok
+        } else {
+            StatusCode::Created
+        };
+        Ok((
+            MutedKeywordResponse {
+                id: row.id,
+                keyword: row.keyword,
+                expires_at: row.expires_at,
+                created_at: row.created_at,
+            },
+            status,
+        ))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for MutedKeywords {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let now = crate::clock::current_time().await;
+
+        let rows = query!(
+            r#"
+                select id, keyword, expires_at, created_at
+                from muted_keywords
+                where user_id = $1 and (expires_at is null or expires_at > $2)
+                order by created_at desc
+            "#,
+            current_user.id,
+            now,
+        )
+        .fetch_all(db_pool)
+        .await?;
+
+        let mutes = rows
+            .into_iter()
+            .map(|row| MutedKeywordResponse {
+                id: row.id,
+                keyword: row.keyword,
+                expires_at: row.expires_at,
+                created_at: row.created_at,
+            })
+            .collect::<Vec<_>>();
+
+        Ok((mutes, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for UnmuteKeyword {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let not_found = || {
+            api_error(StatusCode::NotFound, ErrorCode::MuteNotFound, "Muted keyword not found")
+        };
+        let id = req.param::<Uuid>("id").map_err(|_| not_found())?;
+
+        let rows_deleted = query!(
+            "delete from muted_keywords where id = $1 and user_id = $2",
+            id,
+            current_user.id,
+        )
+        .execute(db_pool)
+        .await?;
+
+        if rows_deleted == 0 {
+            return Err(not_found());
+        }
+
+        Ok(((), StatusCode::Ok))
+    }
+}
@@ -0,0 +1,161 @@
+use crate::errors::api_error;
+use crate::mailer::Email;
+use crate::validation::FieldErrors;
+use crate::{passwords, tokens};
+use crate::{BackendApiEndpoint, State};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use shared::{
+    errors::ErrorCode,
+    payloads::{ConfirmPasswordResetPayload, RequestPasswordResetPayload},
+    ApiEndpoint, ConfirmPasswordReset, RequestPasswordReset,
+};
+use sqlx::query;
+use tide::{Request, StatusCode};
+use uuid::Uuid;
+
+#[async_trait]
+impl BackendApiEndpoint for RequestPasswordReset {
+    async fn handler(
+        req: Request<State>,
+        payload: RequestPasswordResetPayload,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+
+        let user = query!(
+            "select id, username, email from users where lower(email) = lower($1)",
+            payload.email,
+        )
+        .fetch_optional(db_pool)
+        .await?;
+
+        // Respond the same way whether or not the address is known, so this
+        // can't be used to find out who has an account. That includes how
+        // long it takes, so the token and email are dealt with after
+        // responding.
+        if let Some(user) = user {
+            let state = req.state().clone();
+            let now = crate::clock::current_time().await;
+            let to = user.email.unwrap_or(payload.email);
+            let (user_id, username) = (user.id, user.username);
+            async_std::task::spawn(async move {
+                if let Err(err) = send_reset_email(&state, user_id, &username, to, now).await {
+                    log::error!("failed to send password reset email: {}", err);
+                }
+            });
+        }
+
+        Ok(((), StatusCode::Accepted))
+    }
+}
+
+async fn send_reset_email(
+    state: &State,
+    user_id: Uuid,
+    username: &str,
+    to: String,
+    now: DateTime<Utc>,
+) -> tide::Result<()> {
+    let raw_token = tokens::generate();
+    query!(
+        r#"
+            insert into password_resets (
+                id,
+                user_id,
+                token_hash,
+                expires_at,
+                created_at,
+                updated_at
+            )
+            values ($1, $2, $3, $4, $5, $6)
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        tokens::hash(&raw_token)?,
+        now + state.config.password_reset_ttl,
+        now,
+        now,
+    )
+    .execute(&state.db_pool)
+    .await?;
+
+    let email = Email {
+        to,
+        subject: "Reset your Witter password".to_string(),
+        body: format!(
+            "Hi @{},\n\n\
+             Someone asked to reset your password. If it was you, follow the link below. \
+             Otherwise you can ignore this email.\n\n\
+             {}/reset_password?token={}\n\n\
+             The link stops working after it's been used once or in {} minutes.\n",
+            username,
+            state.config.app_url,
+            raw_token,
+            state.config.password_reset_ttl.num_minutes(),
+        ),
+    };
+    state.mailer.send(email).await?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl BackendApiEndpoint for ConfirmPasswordReset {
+    async fn handler(
+        req: Request<State>,
+        payload: ConfirmPasswordResetPayload,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+
+        let mut errors = FieldErrors::new();
+        if payload.new_password.is_empty() {
+            errors.add("new_password", "can't be blank");
+        }
+        errors.into_result()?;
+
+        let hashed_password = passwords::hash(&payload.new_password).await?;
+
+        // Claiming the token and checking it is one statement, so two
+        // requests racing with the same token can't both get through
+        let now = crate::clock::current_time().await;
+        let mut tx = db_pool.begin().await?;
+        let reset = query!(
+            r#"
+                update password_resets
+                set used_at = $1, updated_at = $1
+                where token_hash = $2
+                    and used_at is null
+                    and expires_at > $1
+                returning user_id
+            "#,
+            now,
+            tokens::hash(&payload.token)?,
+        )
+        .fetch_optional(&mut tx)
+        .await?;
+
+        let reset = reset.ok_or_else(|| {
+            api_error(
+                StatusCode::UnprocessableEntity,
+                ErrorCode::InvalidResetToken,
+                "Password reset token is invalid or has expired",
+            )
+        })?;
+
+        query!(
+            "update users set hashed_password = $1, updated_at = $2 where id = $3",
+            hashed_password,
+            now,
+            reset.user_id,
+        )
+        .execute(&mut tx)
+        .await?;
+        // Whoever knew the old password shouldn't stay signed in
+        query!("delete from auth_tokens where user_id = $1", reset.user_id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(((), StatusCode::Ok))
+    }
+}
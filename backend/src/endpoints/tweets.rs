@@ -1,14 +1,23 @@
-use crate::endpoints::authenticate;
+use crate::endpoints::users::load_user;
+use crate::endpoints::{authenticate, authenticate_if_present, is_following, Pagination};
+use crate::errors::api_error;
 use crate::{BackendApiEndpoint, State};
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use shared::errors::ErrorCode;
 use shared::MAX_TWEET_LENGTH;
 use shared::{ApiEndpoint, 
-    payloads::CreateTweetPayload, PostTweet,
-    responses::{PostTweetResponse, TweetResponse, }
+    payloads::{CreateTweetPayload, EditTweetPayload},
+    Conversation, DeleteTweet, EditTweet, GetTweet, Like, NoPayLoad, PostTweet, Retweet,
+    TweetHistory, Unlike, Unretweet, UserLikes, UserTweets,
+    responses::{
+        ConversationResponse, PostTweetResponse, TweetResponse, TweetRevisionResponse,
+        UserResponse,
+    }
 };
-use sqlx::query;
-use tide::{Error, Request, StatusCode};
+use sqlx::{query, PgPool};
+use std::collections::HashMap;
+use tide::{Request, StatusCode};
 use uuid::Uuid;
 
 
@@ -20,24 +29,46 @@ impl BackendApiEndpoint for PostTweet {
     ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
         let db_pool = req.state().db_pool.clone();
 
-        if create_tweet.text.len() > MAX_TWEET_LENGTH {
-            return Err(Error::from_str(
-                StatusCode::UnprocessableEntity,
-                format!("Tweet is too long. Max then is {}", MAX_TWEET_LENGTH),
-            ));
-        }
+        ensure_tweet_length(&create_tweet.text)?;
 
         let user = authenticate(&req).await?;
 
+        let id = Uuid::new_v4();
+        let conversation_id = match create_tweet.in_reply_to_id {
+            Some(in_reply_to_id) => {
+                let parent = load_tweet(&db_pool, in_reply_to_id, Some(user.id)).await?;
+                ensure_can_see_tweets(&db_pool, &parent.user, Some(&user)).await?;
+                parent.conversation_id
+            }
+            None => id,
+        };
+
+        if let Some(quoted_tweet_id) = create_tweet.quoted_tweet_id {
+            let quoted = load_tweet(&db_pool, quoted_tweet_id, Some(user.id)).await?;
+            ensure_can_amplify(&quoted, &user, "Protected tweets can't be quoted")?;
+        }
+
         let now = crate::clock::current_time().await;
         let row = query!(
             r#"
-            insert into tweets (id, user_id, text, created_at, updated_at)
-            values ($1, $2, $3, $4, $5) returning id, text
+            insert into tweets (
+                id,
+                user_id,
+                text,
+                in_reply_to_id,
+                conversation_id,
+                quoted_tweet_id,
+                created_at,
+                updated_at
+            )
+            values ($1, $2, $3, $4, $5, $6, $7, $8) returning id, text
             "#,
-                Uuid::new_v4(),
+                id,
                 user.id,
                 create_tweet.text,
+                create_tweet.in_reply_to_id,
+                conversation_id,
+                create_tweet.quoted_tweet_id,
                 now,
                 now,
             )
@@ -54,3 +85,757 @@ impl BackendApiEndpoint for PostTweet {
     }
 }
 
+
+#[async_trait]
+impl BackendApiEndpoint for UserTweets {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let pagination = req.query::<Pagination>()?;
+        let username = req.param::<String>("username")?;
+
+        let viewer = authenticate_if_present(&req).await?;
+        let user = load_user(db_pool, &username, viewer.as_ref().map(|viewer| viewer.id)).await?;
+
+        ensure_can_see_tweets(db_pool, &user, viewer.as_ref()).await?;
+
+        let sql = format!(
+            r#"
+                select {}
+                from tweets
+                inner join users on users.id = tweets.user_id
+                where tweets.user_id = $1
+                    and {}
+                order by tweets.created_at desc
+                limit $3
+                offset $4
+            "#,
+            TWEET_COLUMNS,
+            VISIBLE_AUTHOR
+        );
+        let tweets = sqlx::query_as::<_, TweetRow>(&sql)
+            .bind(user.id)
+            .bind(viewer.as_ref().map(|viewer| viewer.id))
+            .bind(pagination.limit())
+            .bind(pagination.offset())
+            .fetch_all(db_pool)
+            .await?;
+
+        // `user` says whether the viewer follows them, which the row doesn't
+        let mut tweet_responses = tweets
+            .into_iter()
+            .map(|row| TweetResponse {
+                user: user.clone(),
+                ..TweetResponse::from(row)
+            })
+            .collect::<Vec<_>>();
+        embed_quoted_tweets(
+            db_pool,
+            &mut tweet_responses,
+            viewer.as_ref().map(|viewer| viewer.id),
+        )
+        .await?;
+
+        Ok((tweet_responses, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for GetTweet {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let id = tweet_id(&req)?;
+        let viewer = authenticate_if_present(&req).await?;
+
+        let tweet = load_tweet(db_pool, id, viewer.as_ref().map(|viewer| viewer.id)).await?;
+        ensure_can_see_tweets(db_pool, &tweet.user, viewer.as_ref()).await?;
+
+        Ok((tweet, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for DeleteTweet {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let id = tweet_id(&req)?;
+        let current_user = authenticate(&req).await?;
+
+        let tweet = query!("select user_id from tweets where id = $1", id)
+            .fetch_optional(db_pool)
+            .await?
+            .ok_or_else(tweet_not_found)?;
+
+        if tweet.user_id != current_user.id {
+            return Err(api_error(
+                StatusCode::Forbidden,
+                ErrorCode::Forbidden,
+                "You can only delete your own tweets",
+            ));
+        }
+
+        query!("delete from tweets where id = $1", id)
+            .execute(db_pool)
+            .await?;
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for EditTweet {
+    async fn handler(
+        req: Request<State>,
+        edit_tweet: EditTweetPayload,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let edit_window = req.state().config.tweet_edit_window;
+        let id = tweet_id(&req)?;
+        let current_user = authenticate(&req).await?;
+
+        ensure_tweet_length(&edit_tweet.text)?;
+
+        let now = crate::clock::current_time().await;
+        let mut tx = db_pool.begin().await?;
+        let tweet = query!(
+            "select user_id, text, created_at, updated_at from tweets where id = $1 for update",
+            id,
+        )
+        .fetch_optional(&mut tx)
+        .await?
+        .ok_or_else(tweet_not_found)?;
+
+        if tweet.user_id != current_user.id {
+            return Err(api_error(
+                StatusCode::Forbidden,
+                ErrorCode::Forbidden,
+                "You can only edit your own tweets",
+            ));
+        }
+
+        if now - tweet.created_at > edit_window {
+            return Err(api_error(
+                StatusCode::Forbidden,
+                ErrorCode::EditWindowClosed,
+                format!(
+                    "Tweets can only be edited for {} minutes after posting",
+                    edit_window.num_minutes()
+                ),
+            ));
+        }
+
+        // Saving the same text again isn't an edit, so it leaves no revision
+        if tweet.text != edit_tweet.text {
+            query!(
+                r#"
+                    insert into tweet_revisions (tweet_id, text, created_at)
+                    values ($1, $2, $3)
+                "#,
+                id,
+                tweet.text,
+                tweet.updated_at,
+            )
+            .execute(&mut tx)
+            .await?;
+
+            query!(
+                r#"
+                    update tweets
+                    set text = $2, updated_at = $3, revisions_count = revisions_count + 1
+                    where id = $1
+                "#,
+                id,
+                edit_tweet.text,
+                now,
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        let tweet = load_tweet(db_pool, id, Some(current_user.id)).await?;
+
+        Ok((tweet, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for Retweet {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let id = tweet_id(&req)?;
+        let current_user = authenticate(&req).await?;
+
+        let tweet = load_tweet(db_pool, id, Some(current_user.id)).await?;
+        ensure_can_see_tweets(db_pool, &tweet.user, Some(&current_user)).await?;
+        ensure_can_amplify(&tweet, &current_user, "Protected tweets can't be retweeted")?;
+
+        let rows_inserted = query!(
+            r#"
+                insert into retweets (user_id, tweet_id, created_at)
+                values ($1, $2, $3)
+                on conflict (user_id, tweet_id) do nothing
+            "#,
+            current_user.id,
+            id,
+            crate::clock::current_time().await,
+        )
+        .execute(db_pool)
+        .await?;
+
+        if rows_inserted == 1 {
+            Ok(((), StatusCode::Created))
+        } else {
+            Ok(((), StatusCode::Ok))
+        }
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for Unretweet {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let id = tweet_id(&req)?;
+        let current_user = authenticate(&req).await?;
+
+        query!(
+            "delete from retweets where user_id = $1 and tweet_id = $2",
+            current_user.id,
+            id,
+        )
+        .execute(db_pool)
+        .await?;
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for Like {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let id = tweet_id(&req)?;
+        let current_user = authenticate(&req).await?;
+
+        let tweet = load_tweet(db_pool, id, Some(current_user.id)).await?;
+        ensure_can_see_tweets(db_pool, &tweet.user, Some(&current_user)).await?;
+
+        let rows_inserted = query!(
+            r#"
+                insert into likes (user_id, tweet_id, created_at)
+                values ($1, $2, $3)
+                on conflict (user_id, tweet_id) do nothing
+            "#,
+            current_user.id,
+            id,
+            crate::clock::current_time().await,
+        )
+        .execute(db_pool)
+        .await?;
+
+        if rows_inserted == 1 {
+            Ok(((), StatusCode::Created))
+        } else {
+            Ok(((), StatusCode::Ok))
+        }
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for Unlike {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let id = tweet_id(&req)?;
+        let current_user = authenticate(&req).await?;
+
+        query!(
+            "delete from likes where user_id = $1 and tweet_id = $2",
+            current_user.id,
+            id,
+        )
+        .execute(db_pool)
+        .await?;
+
+        Ok(((), StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for UserLikes {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let pagination = req.query::<Pagination>()?;
+        let username = req.param::<String>("username")?;
+
+        let viewer = authenticate_if_present(&req).await?;
+        let viewer_id = viewer.as_ref().map(|viewer| viewer.id);
+        let user = load_user(db_pool, &username, viewer_id).await?;
+
+        ensure_can_see_tweets(db_pool, &user, viewer.as_ref()).await?;
+
+        let sql = format!(
+            r#"
+                select {}
+                from likes
+                inner join tweets on tweets.id = likes.tweet_id
+                inner join users on users.id = tweets.user_id
+                where likes.user_id = $1
+                    and {}
+                    and {}
+                order by likes.created_at desc
+                limit $3
+                offset $4
+            "#,
+            TWEET_COLUMNS,
+            VISIBLE_AUTHOR,
+            VISIBLE_IF_PROTECTED
+        );
+        let rows = sqlx::query_as::<_, TweetRow>(&sql)
+            .bind(user.id)
+            .bind(viewer_id)
+            .bind(pagination.limit())
+            .bind(pagination.offset())
+            .fetch_all(db_pool)
+            .await?;
+
+        let mut tweets = rows.into_iter().map(TweetResponse::from).collect::<Vec<_>>();
+        embed_quoted_tweets(db_pool, &mut tweets, viewer_id).await?;
+
+        Ok((tweets, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for TweetHistory {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let id = tweet_id(&req)?;
+        let viewer = authenticate_if_present(&req).await?;
+
+        let tweet = load_tweet(db_pool, id, viewer.as_ref().map(|viewer| viewer.id)).await?;
+        ensure_can_see_tweets(db_pool, &tweet.user, viewer.as_ref()).await?;
+
+        let revisions = query!(
+            r#"
+                select id, text, created_at
+                from tweet_revisions
+                where tweet_id = $1
+                order by created_at desc
+            "#,
+            id,
+        )
+        .fetch_all(db_pool)
+        .await?
+        .into_iter()
+        .map(|row| TweetRevisionResponse {
+            id: row.id,
+            text: row.text,
+            created_at: row.created_at,
+        })
+        .collect::<Vec<_>>();
+
+        Ok((revisions, StatusCode::Ok))
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for Conversation {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let pagination = req.query::<Pagination>()?;
+        let id = tweet_id(&req)?;
+        let viewer = authenticate_if_present(&req).await?;
+        let viewer_id = viewer.as_ref().map(|viewer| viewer.id);
+
+        let tweet = load_tweet(db_pool, id, viewer_id).await?;
+        ensure_can_see_tweets(db_pool, &tweet.user, viewer.as_ref()).await?;
+
+        // Walks up from the tweet one parent at a time. A hidden ancestor
+        // leaves a gap rather than cutting the chain short.
+        let ancestors_sql = format!(
+            r#"
+                with recursive ancestors (id, depth) as (
+                    select in_reply_to_id, 1
+                    from tweets
+                    where id = $1 and in_reply_to_id is not null
+                    union all
+                    select tweets.in_reply_to_id, ancestors.depth + 1
+                    from tweets
+                    inner join ancestors on ancestors.id = tweets.id
+                    where tweets.in_reply_to_id is not null
+                )
+                select {}
+                from ancestors
+                inner join tweets on tweets.id = ancestors.id
+                inner join users on users.id = tweets.user_id
+                where {}
+                    and {}
+                order by ancestors.depth desc
+            "#,
+            TWEET_COLUMNS,
+            VISIBLE_AUTHOR,
+            VISIBLE_IF_PROTECTED
+        );
+        let ancestors = sqlx::query_as::<_, TweetRow>(&ancestors_sql)
+            .bind(id)
+            .bind(viewer_id)
+            .fetch_all(db_pool)
+            .await?;
+
+        let replies_sql = format!(
+            r#"
+                with recursive replies (id) as (
+                    select id from tweets where in_reply_to_id = $1
+                    union all
+                    select tweets.id
+                    from tweets
+                    inner join replies on tweets.in_reply_to_id = replies.id
+                )
+                select {}
+                from replies
+                inner join tweets on tweets.id = replies.id
+                inner join users on users.id = tweets.user_id
+                where {}
+                    and {}
+                order by tweets.created_at, tweets.id
+                limit $3
+                offset $4
+            "#,
+            TWEET_COLUMNS,
+            VISIBLE_AUTHOR,
+            VISIBLE_IF_PROTECTED
+        );
+        let replies = sqlx::query_as::<_, TweetRow>(&replies_sql)
+            .bind(id)
+            .bind(viewer_id)
+            .bind(pagination.limit())
+            .bind(pagination.offset())
+            .fetch_all(db_pool)
+            .await?;
+
+        let mut ancestors = ancestors.into_iter().map(TweetResponse::from).collect::<Vec<_>>();
+        embed_quoted_tweets(db_pool, &mut ancestors, viewer_id).await?;
+        let mut replies = replies.into_iter().map(TweetResponse::from).collect::<Vec<_>>();
+        embed_quoted_tweets(db_pool, &mut replies, viewer_id).await?;
+
+        Ok((
+            ConversationResponse {
+                ancestors,
+                tweet,
+                replies,
+            },
+            StatusCode::Ok,
+        ))
+    }
+}
+
+/// The columns a `TweetRow` is read from, for splicing into queries that
+/// join `tweets` to its author as `users`. `liked_by_me` is for the viewer
+/// bound as `$2`, and is null when that's null.
+pub(crate) const TWEET_COLUMNS: &str = r#"
+    tweets.id,
+    tweets.text,
+    tweets.created_at,
+    case
+        when tweets.updated_at > tweets.created_at then tweets.updated_at
+    end as edited_at,
+    tweets.revisions_count,
+    tweets.in_reply_to_id,
+    tweets.conversation_id,
+    tweets.replies_count,
+    tweets.retweets_count,
+    tweets.quoted_tweet_id,
+    tweets.likes_count,
+    case when $2::uuid is null then null else exists (
+        select 1 from likes as viewer_likes
+        where viewer_likes.user_id = $2 and viewer_likes.tweet_id = tweets.id
+    ) end as liked_by_me,
+    users.id as user_id,
+    users.username,
+    users.display_name,
+    users.bio,
+    users.location,
+    users.website,
+    users.avatar_url,
+    users.avatar_thumbnail_url,
+    users.header_url,
+    users.protected,
+    users.followers_count,
+    users.following_count,
+    users.tweets_count
+"#;
+
+/// Leaves out authors (`users`) who are deactivated or on either side of a
+/// block with the viewer bound as `$2`.
+pub(crate) const VISIBLE_AUTHOR: &str = r#"
+    users.deactivated_at is null
+    and not exists (
+        select 1 from blocks
+        where (blocks.blocker_id = $2 and blocks.blocked_id = users.id)
+            or (blocks.blocker_id = users.id and blocks.blocked_id = $2)
+    )
+"#;
+
+/// Leaves out protected authors (`users`) unless the viewer bound as `$2` is
+/// them or follows them. Lookups of a single tweet leave this out and use
+/// `ensure_can_see_tweets` instead, which says why with a 403.
+pub(crate) const VISIBLE_IF_PROTECTED: &str = r#"
+    (
+        not users.protected
+        or users.id = $2
+        or exists (
+            select 1 from follows
+            where follows.follower_id = $2 and follows.followee_id = users.id
+        )
+    )
+"#;
+
+/// A tweet joined with its author, as selected by `TWEET_COLUMNS`.
+#[derive(sqlx::FromRow)]
+pub(crate) struct TweetRow {
+    id: Uuid,
+    text: String,
+    created_at: DateTime<Utc>,
+    edited_at: Option<DateTime<Utc>>,
+    revisions_count: i64,
+    in_reply_to_id: Option<Uuid>,
+    conversation_id: Uuid,
+    replies_count: i64,
+    retweets_count: i64,
+    quoted_tweet_id: Option<Uuid>,
+    likes_count: i64,
+    liked_by_me: Option<bool>,
+    user_id: Uuid,
+    username: String,
+    display_name: Option<String>,
+    bio: Option<String>,
+    location: Option<String>,
+    website: Option<String>,
+    avatar_url: Option<String>,
+    avatar_thumbnail_url: Option<String>,
+    header_url: Option<String>,
+    protected: bool,
+    followers_count: i64,
+    following_count: i64,
+    tweets_count: i64,
+}
+
+impl From<TweetRow> for TweetResponse {
+    fn from(row: TweetRow) -> Self {
+        TweetResponse {
+            id: row.id,
+            text: row.text,
+            created_at: row.created_at,
+            edited_at: row.edited_at,
+            revision_count: row.revisions_count,
+            in_reply_to_id: row.in_reply_to_id,
+            conversation_id: row.conversation_id,
+            reply_count: row.replies_count,
+            retweet_count: row.retweets_count,
+            like_count: row.likes_count,
+            liked_by_me: row.liked_by_me,
+            quoted_tweet_id: row.quoted_tweet_id,
+            quoted_tweet: None,
+            retweeted_by: None,
+            user: UserResponse {
+                id: row.user_id,
+                username: row.username,
+                display_name: row.display_name,
+                bio: row.bio,
+                location: row.location,
+                website: row.website,
+                avatar_url: row.avatar_url,
+                avatar_thumbnail_url: row.avatar_thumbnail_url,
+                header_url: row.header_url,
+                protected: row.protected,
+                followers_count: row.followers_count,
+                following_count: row.following_count,
+                tweets_count: row.tweets_count,
+                followed_by_me: None,
+                follows_me: None,
+            },
+        }
+    }
+}
+
+/// A tweet along with its author. Tweets by deleted users, or by users who
+/// have blocked `viewer_id` or been blocked by them, are a 404.
+pub(crate) async fn load_tweet(
+    db_pool: &PgPool,
+    id: Uuid,
+    viewer_id: Option<Uuid>,
+) -> tide::Result<TweetResponse> {
+    let sql = format!(
+        r#"
+            select {}
+            from tweets
+            inner join users on users.id = tweets.user_id
+            where tweets.id = $1
+                and {}
+        "#,
+        TWEET_COLUMNS,
+        VISIBLE_AUTHOR
+    );
+    let row = sqlx::query_as::<_, TweetRow>(&sql)
+        .bind(id)
+        .bind(viewer_id)
+        .fetch_optional(db_pool)
+        .await?
+        .ok_or_else(tweet_not_found)?;
+
+    let mut tweets = vec![TweetResponse::from(row)];
+    embed_quoted_tweets(db_pool, &mut tweets, viewer_id).await?;
+    Ok(tweets.remove(0))
+}
+
+/// Fills in `quoted_tweet` for the tweets that quote one `viewer_id` is
+/// allowed to see.
+pub(crate) async fn embed_quoted_tweets(
+    db_pool: &PgPool,
+    tweets: &mut [TweetResponse],
+    viewer_id: Option<Uuid>,
+) -> tide::Result<()> {
+    let ids = tweets
+        .iter()
+        .filter_map(|tweet| tweet.quoted_tweet_id)
+        .collect::<Vec<_>>();
+    let quoted = load_tweets_by_id(db_pool, &ids, viewer_id).await?;
+
+    for tweet in tweets.iter_mut() {
+        tweet.quoted_tweet = tweet
+            .quoted_tweet_id
+            .and_then(|id| quoted.get(&id))
+            .map(|quoted| Box::new(quoted.clone()));
+    }
+    Ok(())
+}
+
+/// Tweets by id that `viewer_id` is allowed to see, without their quoted
+/// tweets. Hidden or missing ids are simply absent from the map.
+pub(crate) async fn load_tweets_by_id(
+    db_pool: &PgPool,
+    ids: &[Uuid],
+    viewer_id: Option<Uuid>,
+) -> tide::Result<HashMap<Uuid, TweetResponse>> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let sql = format!(
+        r#"
+            select {}
+            from tweets
+            inner join users on users.id = tweets.user_id
+            where tweets.id = any($1)
+                and {}
+                and {}
+        "#,
+        TWEET_COLUMNS,
+        VISIBLE_AUTHOR,
+        VISIBLE_IF_PROTECTED
+    );
+    let tweets = sqlx::query_as::<_, TweetRow>(&sql)
+        .bind(ids)
+        .bind(viewer_id)
+        .fetch_all(db_pool)
+        .await?
+    .into_iter()
+    .map(|row| (row.id, TweetResponse::from(row)))
+    .collect::<HashMap<_, _>>();
+
+    Ok(tweets)
+}
+
+/// Retweets and quotes would show protected tweets to people who don't
+/// follow their author, so only the author can amplify those.
+fn ensure_can_amplify(
+    tweet: &TweetResponse,
+    user: &UserResponse,
+    message: &str,
+) -> tide::Result<()> {
+    if tweet.user.protected && tweet.user.id != user.id {
+        return Err(api_error(
+            StatusCode::Forbidden,
+            ErrorCode::CannotRetweetProtected,
+            message,
+        ));
+    }
+    Ok(())
+}
+
+/// Protected accounts only show their tweets to themselves and their
+/// followers.
+pub(crate) async fn ensure_can_see_tweets(
+    db_pool: &PgPool,
+    author: &UserResponse,
+    viewer: Option<&UserResponse>,
+) -> tide::Result<()> {
+    if !author.protected {
+        return Ok(());
+    }
+
+    let can_see = match viewer {
+        Some(viewer) => {
+            viewer.id == author.id || is_following(db_pool, viewer.id, author.id).await?
+        }
+        None => false,
+    };
+    if can_see {
+        Ok(())
+    } else {
+        Err(api_error(
+            StatusCode::Forbidden,
+            ErrorCode::ProtectedAccount,
+            "This account's tweets are protected",
+        ))
+    }
+}
+
+fn ensure_tweet_length(text: &str) -> tide::Result<()> {
+    if text.len() > MAX_TWEET_LENGTH {
+        return Err(api_error(
+            StatusCode::UnprocessableEntity,
+            ErrorCode::TweetTooLong,
+            format!("Tweet is too long. Max then is {}", MAX_TWEET_LENGTH),
+        ));
+    }
+    Ok(())
+}
+
+/// Ids that don't parse can't exist either, so they're a 404 too.
+fn tweet_id(req: &Request<State>) -> tide::Result<Uuid> {
+    req.param::<Uuid>("id").map_err(|_| tweet_not_found())
+}
+
+fn tweet_not_found() -> tide::Error {
+    api_error(StatusCode::NotFound, ErrorCode::TweetNotFound, "Tweet not found")
+}
@@ -0,0 +1,243 @@
+use crate::blobs::{self, Blob, BlobStore};
+use crate::endpoints::authenticate;
+use crate::errors::api_error;
+use crate::images::{self, ImageKind, Rendition};
+use crate::validation::FieldErrors;
+use crate::{BackendApiEndpoint, State};
+use async_trait::async_trait;
+use futures::prelude::*;
+use shared::errors::ErrorCode;
+use shared::responses::UserResponse;
+use shared::{NoPayLoad, UploadAvatar, UploadHeader, MAX_IMAGE_UPLOAD_SIZE};
+use sqlx::query;
+use tide::{Body, Request, Response, StatusCode};
+use uuid::Uuid;
+
+/// Room for the multipart boundaries and part headers around the image.
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
+/// Blob keys are never reused, so what's behind a URL never changes.
+const BLOB_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+#[async_trait]
+impl BackendApiEndpoint for UploadAvatar {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(UserResponse, StatusCode)> {
+        upload(req, ImageKind::Avatar).await
+    }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for UploadHeader {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(UserResponse, StatusCode)> {
+        upload(req, ImageKind::Header).await
+    }
+}
+
+async fn upload(
+    mut req: Request<State>,
+    kind: ImageKind,
+) -> tide::Result<(UserResponse, StatusCode)> {
+    let user = authenticate(&req).await?;
+    let (content_type, bytes) = read_image_part(&mut req).await?;
+
+    let renditions = async_std::task::spawn_blocking(move || {
+        images::process(kind, &content_type, &bytes)
+    })
+    .await
+    .map_err(|err| {
+        api_error(
+            StatusCode::UnprocessableEntity,
+            ErrorCode::UnsupportedImage,
+            err.to_string(),
+        )
+    })?;
+
+    let state = req.state();
+    let key = format!("{}/{}/{}", kind.key_prefix(), user.id, Uuid::new_v4());
+    for Rendition { name, bytes } in renditions {
+        let blob = Blob {
+            bytes,
+            content_type: Rendition::CONTENT_TYPE.to_string(),
+        };
+        state.blobs.put(&blob_key(&key, name), blob).await?;
+    }
+    let url = |name| format!("{}/blobs/{}", state.config.public_url, blob_key(&key, name));
+
+    let now = crate::clock::current_time().await;
+    // The row stays locked until commit, so two uploads at once can't both
+    // replace the same previous key and leave one set of files behind
+    let mut tx = state.db_pool.begin().await?;
+    let (user, previous_key) = match kind {
+        ImageKind::Avatar => {
+            let previous = query!(
+                "select avatar_key from users where id = $1 for update",
+                user.id
+            )
+            .fetch_one(&mut tx)
+            .await?
+            .avatar_key;
+            let user = UserResponse {
+                avatar_url: Some(url("full")),
+                avatar_thumbnail_url: Some(url("thumbnail")),
+                ..user
+            };
+            query!(
+                r#"
+                    update users
+                    set avatar_key = $1,
+                        avatar_url = $2,
+                        avatar_thumbnail_url = $3,
+                        updated_at = $4
+                    where id = $5
+                "#,
+                key,
+                user.avatar_url,
+                user.avatar_thumbnail_url,
+                now,
+                user.id,
+            )
+            .execute(&mut tx)
+            .await?;
+            (user, previous)
+        }
+        ImageKind::Header => {
+            let previous = query!(
+                "select header_key from users where id = $1 for update",
+                user.id
+            )
+            .fetch_one(&mut tx)
+            .await?
+            .header_key;
+            let user = UserResponse {
+                header_url: Some(url("full")),
+                ..user
+            };
+            query!(
+                r#"
+                    update users
+                    set header_key = $1, header_url = $2, updated_at = $3
+                    where id = $4
+                "#,
+                key,
+                user.header_url,
+                now,
+                user.id,
+            )
+            .execute(&mut tx)
+            .await?;
+            (user, previous)
+        }
+    };
+    tx.commit().await?;
+
+    if let Some(previous_key) = previous_key {
+        delete_renditions(&*state.blobs, kind, &previous_key).await;
+    }
+
+    Ok((user, StatusCode::Created))
+}
+
+/// The old files are only clutter once nothing points at them, so failing
+/// to remove them shouldn't fail whatever replaced or removed them.
+pub(crate) async fn delete_renditions(blobs: &dyn BlobStore, kind: ImageKind, key: &str) {
+    for name in kind.rendition_names() {
+        let blob_key = blob_key(key, name);
+        if let Err(err) = blobs.delete(&blob_key).await {
+            log::error!("failed to delete blob {}: {}", blob_key, err);
+        }
+    }
+}
+
+fn blob_key(key: &str, rendition_name: &str) -> String {
+    format!("{}/{}.{}", key, rendition_name, Rendition::EXTENSION)
+}
+
+/// Reads the `image` part of a `multipart/form-data` body, returning its
+/// content type and contents.
+async fn read_image_part(req: &mut Request<State>) -> tide::Result<(String, Vec<u8>)> {
+    let content_type = req
+        .content_type()
+        .map(|mime| mime.to_string())
+        .unwrap_or_default();
+    let boundary = multer::parse_boundary(&content_type).map_err(|_| {
+        api_error(
+            StatusCode::BadRequest,
+            ErrorCode::BadRequest,
+            "Expected a multipart/form-data body",
+        )
+    })?;
+
+    let max_body_size = MAX_IMAGE_UPLOAD_SIZE + MULTIPART_OVERHEAD;
+    let mut body = Vec::new();
+    (&mut *req)
+        .take(max_body_size as u64 + 1)
+        .read_to_end(&mut body)
+        .await?;
+    if body.len() > max_body_size {
+        return Err(image_too_large());
+    }
+
+    let body = stream::once(async move { Ok::<_, std::io::Error>(bytes::Bytes::from(body)) });
+    let mut multipart = multer::Multipart::new(body, boundary);
+    while let Some(field) = multipart.next_field().await.map_err(bad_multipart)? {
+        if field.name() != Some("image") {
+            continue;
+        }
+
+        let content_type = field
+            .content_type()
+            .map(|mime| mime.to_string())
+            .unwrap_or_default();
+        let bytes = field.bytes().await.map_err(bad_multipart)?;
+        if bytes.len() > MAX_IMAGE_UPLOAD_SIZE {
+            return Err(image_too_large());
+        }
+        return Ok((content_type, bytes.to_vec()));
+    }
+
+    let mut errors = FieldErrors::new();
+    errors.add("image", "can't be blank");
+    Err(tide::Error::new(StatusCode::UnprocessableEntity, errors))
+}
+
+fn bad_multipart(err: multer::Error) -> tide::Error {
+    api_error(
+        StatusCode::BadRequest,
+        ErrorCode::BadRequest,
+        format!("Invalid multipart body: {}", err),
+    )
+}
+
+fn image_too_large() -> tide::Error {
+    api_error(
+        StatusCode::PayloadTooLarge,
+        ErrorCode::ImageTooLarge,
+        format!(
+            "Image is too large (maximum is {} MB)",
+            MAX_IMAGE_UPLOAD_SIZE / 1024 / 1024
+        ),
+    )
+}
+
+/// `GET /blobs/*key`, the files behind the URLs handed out by uploads.
+pub async fn serve_blob(req: Request<State>) -> tide::Result {
+    let key = req.param::<String>("key")?;
+    let not_found = || api_error(StatusCode::NotFound, ErrorCode::NotFound, "File not found");
+
+    if !blobs::is_valid_key(&key) {
+        return Err(not_found());
+    }
+    let blob = req.state().blobs.get(&key).await?.ok_or_else(not_found)?;
+
+    let mut resp = Response::new(StatusCode::Ok);
+    resp.set_body(Body::from_bytes(blob.bytes));
+    resp.insert_header("Content-Type", blob.content_type);
+    resp.insert_header("Cache-Control", BLOB_CACHE_CONTROL);
+    Ok(resp)
+}
@@ -1,22 +1,24 @@
-use super::{authenticate, empty_response, get_auth_token, something_went_wrong};
-use crate::env;
-use crate::responses::BuildApiResponse;
+use super::blocks::has_blocked;
+use super::{
+    authenticate, authenticate_if_present, client_ip, create_session, get_auth_token,
+    is_following, Pagination,
+};
+use crate::errors::api_error;
+use crate::login_throttle;
+use crate::passwords;
+use crate::tokens;
+use crate::validation::{validate_username, FieldErrors};
 use crate::{BackendApiEndpoint, State};
-use argonautica::{Hasher, Verifier};
 use async_trait::async_trait;
-use chrono::prelude::*;
-use failure::Fail;
-use futures::compat::Compat01As03;
-use rand::distributions::Alphanumeric;
-use rand::rngs::OsRng;
-use rand::Rng;
-use serde_json::Value;
+use shared::errors::ErrorCode;
 use shared::payloads::*;
 use shared::{
     responses::{TokenResponse, UserResponse},
     *,
 };
-use sqlx::{query, query_as, PgPool};
+use sqlx::postgres::PgRow;
+use sqlx::{query, query_as, FromRow, PgPool, Row};
+use std::collections::HashMap;
 use tide::Request;
 use tide::{Error, StatusCode};
 use uuid::Uuid;
@@ -29,75 +31,102 @@ impl BackendApiEndpoint for CreateUser {
     ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
         let db_pool = &req.state().db_pool;
     
-        if username_already_claimed(&create_user.username, &db_pool).await? {
-            return Err(Error::from_str(
-                StatusCode::UnprocessableEntity,
-                "Username is already claimed",
-            ));
+        let email = create_user
+            .email
+            .as_deref()
+            .map(str::trim)
+            .filter(|email| !email.is_empty());
+
+        let mut errors = FieldErrors::new();
+        validate_username(&create_user.username, &mut errors);
+        if create_user.password.is_empty() {
+            errors.add("password", "can't be blank");
         }
-    
-        let secret_key = std::env::var("SECRET_KEY")?;
-        let clear_text_password = create_user.password.clone();
-        let mut hasher = Hasher::default();
-    
-        if env::current().is_test() {
-            hasher.configure_iterations(1);
+        if let Some(email) = email {
+            if !email.contains('@') {
+                errors.add("email", "is not a valid address");
+            }
+        }
+        errors.into_result()?;
+
+        let username_claimed = username_already_claimed(&create_user.username, &db_pool).await?;
+        let email_claimed = match email {
+            Some(email) => email_already_claimed(email, &db_pool).await?,
+            None => false,
+        };
+
+        if username_claimed || email_claimed {
+            if req.state().config.conceal_claimed_usernames {
+                // Hash anyway so this takes as long as a signup that succeeds
+                passwords::hash(&create_user.password).await?;
+                return Err(account_unavailable());
+            }
+
+            let mut errors = FieldErrors::new().with_code(if username_claimed {
+                ErrorCode::UsernameTaken
+            } else {
+                ErrorCode::EmailTaken
+            });
+            if username_claimed {
+                errors.add("username", "is already claimed");
+            }
+            if email_claimed {
+                errors.add("email", "is already in use");
+            }
+            errors.into_result()?;
         }
     
-        let hashed_password = Compat01As03::new(
-            hasher
-                .with_password(clear_text_password)
-                .with_secret_key(secret_key)
-                .hash_non_blocking(),
-        )
-        .await
-        .map_err(|err| err.compat())?;
+        let hashed_password = passwords::hash(&create_user.password).await?;
     
         let now = crate::clock::current_time().await;
         let row = query!(
             r#"
-                insert into users (id, username, hashed_password, created_at, updated_at)
-                values ($1, $2, $3, $4, $5) returning id
+                insert into users (id, username, email, hashed_password, created_at, updated_at)
+                values ($1, $2, $3, $4, $5, $6) returning id
             "#,
             Uuid::new_v4(),
             create_user.username,
+            email,
             hashed_password,
             now,
             now,
         )
         .fetch_one(db_pool)
         .await?;
-        let user_id = row.id;
-    
-        let raw_token: String = OsRng.sample_iter(&Alphanumeric).take(32).collect();
-        let token = query!(
-            r#"
-                        insert into auth_tokens (
-                            id,
-                            user_id,
-                            token,
-                            created_at,
-                            updated_at
-                        )
-                        values ($1, $2, $3, $4, $5) returning token
-                    "#,
-            Uuid::new_v4(),
-            user_id,
-            raw_token,
-            now,
-            now,
-        )
-        .fetch_one(db_pool)
-        .await?;
+        let token = create_session(&req, row.id).await?;
     
-        Ok((TokenResponse::new(&token.token), StatusCode::Created))
+        Ok((TokenResponse::new(&token), StatusCode::Created))
         }
 }
 
 //pub async fn create(mut req: Request<State>) -> tide::Result {
 
+fn account_unavailable() -> Error {
+    api_error(
+        StatusCode::UnprocessableEntity,
+        ErrorCode::AccountUnavailable,
+        "An account can't be created with these details",
+    )
+}
+
+fn invalid_credentials() -> Error {
+    api_error(
+        StatusCode::Forbidden,
+        ErrorCode::InvalidCredentials,
+        "Invalid username or password",
+    )
+}
+
 async fn username_already_claimed(username: &str, db_pool: &PgPool) -> tide::Result<bool> {
-    let row = query!("select 1 as one from users where username = $1", username)
+    let row = query!("select 1 as one from users where lower(username) = lower($1)", username)
+        .fetch_optional(db_pool)
+        .await?;
+
+    Ok(row.is_some())
+}
+
+async fn email_already_claimed(email: &str, db_pool: &PgPool) -> tide::Result<bool> {
+    let row = query!("select 1 as one from users where lower(email) = lower($1)", email)
         .fetch_optional(db_pool)
         .await?;
 
@@ -114,12 +143,17 @@ impl BackendApiEndpoint for Login {
         let password = payload.password;
     
         let db_pool = req.state().db_pool.clone();
+        let config = &req.state().config;
+        let ip_address = client_ip(&req);
+        let ip_address = ip_address.as_deref();
+
+        login_throttle::check(&db_pool, config, &username, ip_address).await?;
     
         let user = query!(
             r#"
-                select id, hashed_password
+                select id, hashed_password, deactivated_at
                 from users
-                where username = $1
+                where lower(username) = lower($1)
             "#,
             username
         )
@@ -127,157 +161,358 @@ impl BackendApiEndpoint for Login {
         .await?;
         let user = match user {
             Some(user) => user,
-            None => return Err(Error::from_str(StatusCode::NotFound, "User not found")),
+            None => {
+                // Unknown usernames have to look exactly like a wrong password,
+                // down to how long the response takes
+                passwords::verify_dummy(&password).await?;
+                login_throttle::record_failure(&db_pool, config, &username, ip_address).await?;
+                return Err(invalid_credentials());
+            }
         };
-        let user_password = user.hashed_password;
-    
-        let secret_key = std::env::var("SECRET_KEY")?;
-        let mut verifier = Verifier::default();
-        let is_valid = Compat01As03::new(
-            verifier
-                .with_hash(user_password)
-                .with_password(password)
-                .with_secret_key(secret_key)
-                .verify_non_blocking(),
-        )
-        .await
-        .map_err(|err| err.compat())?;
+        let is_valid = passwords::verify(&user.hashed_password, &password).await?;
     
         if is_valid {
-            let token_row = query!(
+            login_throttle::reset(&db_pool, &username).await?;
+            // Logging in during the grace period takes back a deletion
+            if user.deactivated_at.is_some() {
+                query!(
+                    "update users set deactivated_at = null, updated_at = $1 where id = $2",
+                    crate::clock::current_time().await,
+                    user.id,
+                )
+                .execute(&db_pool)
+                .await?;
+            }
+            let token = create_session(&req, user.id).await?;
+    
+            Ok((TokenResponse::new(&token), StatusCode::Created))
+        } else {
+            login_throttle::record_failure(&db_pool, config, &username, ip_address).await?;
+            Err(invalid_credentials())
+        }
+        }
+}
+
+#[async_trait]
+impl BackendApiEndpoint for Follow {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let username = req.param::<String>("username")?;
+        let followee = find_user(db_pool, &username, Some(current_user.id)).await?;
+        let followee_id = followee.id;
+
+        if current_user.id == followee_id {
+            return Err(api_error(
+                StatusCode::UnprocessableEntity,
+                ErrorCode::CannotFollowSelf,
+                "You cannot follow yourself",
+            ));
+        }
+        if has_blocked(db_pool, current_user.id, followee_id).await? {
+            return Err(api_error(
+                StatusCode::UnprocessableEntity,
+                ErrorCode::UserBlocked,
+                "You have blocked this user",
+            ));
+        }
+
+        let now = crate::clock::current_time().await;
+
+        if followee.protected && !is_following(db_pool, current_user.id, followee_id).await? {
+            query!(
                 r#"
-                    select token
-                    from auth_tokens
-                    where user_id = $1
+                    insert into follow_requests (
+                        id,
+                        requester_id,
+                        target_id,
+                        created_at,
+                        updated_at
+                    )
+                    values ($1, $2, $3, $4, $5)
+                    on conflict (requester_id, target_id) do nothing
                 "#,
-                user.id
+                Uuid::new_v4(),
+                current_user.id,
+                followee_id,
+                now,
+                now,
             )
-            .fetch_one(&db_pool)
+            .execute(db_pool)
             .await?;
-    
-            Ok((TokenResponse::new(&token_row.token), StatusCode::Created))
-        } else {
-            Err(something_went_wrong(StatusCode::Forbidden))
-            // Ok(Response::new(StatusCode::Forbidden))
+
+            return Ok(((), StatusCode::Accepted));
         }
+
+        let rows_inserted = query!(
+            r#"
+                insert into follows (id, follower_id, followee_id, created_at, updated_at)
+                values ($1, $2, $3, $4, $5)
+                on conflict (follower_id, followee_id) do nothing
+            "#,
+            Uuid::new_v4(),
+            current_user.id,
+            followee_id,
+            now,
+            now,
+        )
+        .execute(db_pool)
+        .await?;
+
+        // Following someone you already follow changes nothing
+        if rows_inserted == 1 {
+            Ok(((), StatusCode::Created))
+        } else {
+            Ok(((), StatusCode::Ok))
         }
+    }
 }
 
-pub async fn follow(req: Request<State>) -> tide::Result {
-    let db_pool = req.state().db_pool.clone();
-    let current_user = authenticate(&req).await?;
-    let username = req.param::<String>("username")?;
+#[async_trait]
+impl BackendApiEndpoint for Unfollow {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = &req.state().db_pool;
+        let current_user = authenticate(&req).await?;
+        let username = req.param::<String>("username")?;
+        let followee_id = find_user(db_pool, &username, Some(current_user.id)).await?.id;
 
-    let row = query!("select id from users where username = $1", username)
-        .fetch_optional(&db_pool)
+        query!(
+            "delete from follows where follower_id = $1 and followee_id = $2",
+            current_user.id,
+            followee_id,
+        )
+        .execute(db_pool)
+        .await?;
+        query!(
+            "delete from follow_requests where requester_id = $1 and target_id = $2",
+            current_user.id,
+            followee_id,
+        )
+        .execute(db_pool)
         .await?;
-     let followee_id: Uuid = if let Some(row) = row { row.id } else { todo!() };
 
-    if current_user.id == followee_id {
-        return Err(Error::from_str(
-            StatusCode::UnprocessableEntity,
-            "You cannot follow yourself",
-        ));
+        Ok(((), StatusCode::Ok))
     }
+}
 
-    if user_following(current_user.id, followee_id, &db_pool).await? {
-        return Err(Error::from_str(
-            StatusCode::UnprocessableEntity,
-            "You cannot follow the same user twice",
-        ));
-    }
+/// The columns a `UserRow` is read from, for splicing into queries that
+/// select from `users` under that name.
+pub(crate) const USER_COLUMNS: &str = r#"
+    users.id,
+    users.username,
+    users.display_name,
+    users.bio,
+    users.location,
+    users.website,
+    users.avatar_url,
+    users.avatar_thumbnail_url,
+    users.header_url,
+    users.protected,
+    users.followers_count,
+    users.following_count,
+    users.tweets_count
+"#;
 
-    let now = crate::clock::current_time().await;
-    let rows_inserted = query!(
-        r#"
-            insert into follows (id, follower_id, followee_id, created_at, updated_at)
-            values ($1, $2, $3, $4, $5)
-        "#,
-        Uuid::new_v4(),
-        current_user.id,
-        followee_id,
-        now,
-        now,
-    )
-    .execute(&db_pool)
-    .await?;
+/// `followed_by_me` and `follows_me` for the viewer bound as `$2`, both null
+/// when that's null. Selected after `USER_COLUMNS` and read back with
+/// `user_with_relationship`.
+const RELATIONSHIP_COLUMNS: &str = r#"
+    case when $2::uuid is null then null else exists (
+        select 1 from follows
+        where follows.follower_id = $2 and follows.followee_id = users.id
+    ) end as followed_by_me,
+    case when $2::uuid is null then null else exists (
+        select 1 from follows
+        where follows.follower_id = users.id and follows.followee_id = $2
+    ) end as follows_me
+"#;
+
+/// A user as selected by `USER_COLUMNS`.
+#[derive(sqlx::FromRow)]
+pub(crate) struct UserRow {
+    id: Uuid,
+    username: String,
+    display_name: Option<String>,
+    bio: Option<String>,
+    location: Option<String>,
+    website: Option<String>,
+    avatar_url: Option<String>,
+    avatar_thumbnail_url: Option<String>,
+    header_url: Option<String>,
+    protected: bool,
+    followers_count: i64,
+    following_count: i64,
+    tweets_count: i64,
+}
 
-    if rows_inserted == 1 {
-        Value::Null.to_response_with_status(StatusCode::Created)
-    } else {
-        todo!()
+impl From<UserRow> for UserResponse {
+    fn from(row: UserRow) -> Self {
+        UserResponse {
+            id: row.id,
+            username: row.username,
+            display_name: row.display_name,
+            bio: row.bio,
+            location: row.location,
+            website: row.website,
+            avatar_url: row.avatar_url,
+            avatar_thumbnail_url: row.avatar_thumbnail_url,
+            header_url: row.header_url,
+            protected: row.protected,
+            followers_count: row.followers_count,
+            following_count: row.following_count,
+            tweets_count: row.tweets_count,
+            followed_by_me: None,
+            follows_me: None,
+        }
     }
 }
 
-pub async fn following(req: Request<State>) -> tide::Result {
-    let db_pool = req.state().db_pool.clone();
-    let username = req.param::<String>("username")?;
+/// For rows that select `RELATIONSHIP_COLUMNS` along with `USER_COLUMNS`.
+fn user_with_relationship(row: &PgRow) -> Result<UserResponse, sqlx::Error> {
+    Ok(UserResponse {
+        followed_by_me: row.try_get("followed_by_me")?,
+        follows_me: row.try_get("follows_me")?,
+        ..UserRow::from_row(row)?.into()
+    })
+}
 
-    // TODO: extract this into function
-    let row = query!("select id from users where username = $1", username)
-        .fetch_optional(&db_pool)
-        .await?;
-    let user_id: Uuid = if let Some(row) = row { row.id } else { todo!() };
+pub(crate) struct FoundUser {
+    pub id: Uuid,
+    pub protected: bool,
+}
 
-    let rows = query_as!(
-        UserResponse,
+/// 404s for unknown and deleted users alike, and for users who have blocked
+/// `viewer_id`.
+pub(crate) async fn find_user(
+    db_pool: &PgPool,
+    username: &str,
+    viewer_id: Option<Uuid>,
+) -> tide::Result<FoundUser> {
+    let user = query_as!(
+        FoundUser,
         r#"
-            select users.id, users.username
+            select id, protected
             from users
-            inner join follows on
-                follows.follower_id = $1
-                and follows.followee_id = users.id
+            where lower(username) = lower($1)
+                and deactivated_at is null
+                and not exists (
+                    select 1 from blocks
+                    where blocks.blocker_id = users.id and blocks.blocked_id = $2
+                )
         "#,
-        user_id,
+        username,
+        viewer_id,
     )
-    .fetch_all(&db_pool)
+    .fetch_optional(db_pool)
     .await?;
 
-    rows.to_response()
+    user.ok_or_else(user_not_found)
 }
 
-pub async fn followers(req: Request<State>) -> tide::Result {
-    let db_pool = req.state().db_pool.clone();
-    let username = req.param::<String>("username")?;
+fn user_not_found() -> Error {
+    api_error(StatusCode::NotFound, ErrorCode::UserNotFound, "User not found")
+}
 
-    let row = query!("select id from users where username = $1", username)
-        .fetch_optional(&db_pool)
-        .await?;
-    let user_id: Uuid = if let Some(row) = row { row.id } else { todo!() };
+#[async_trait]
+impl BackendApiEndpoint for Following {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = req.state().db_pool.clone();
+        let username = req.param::<String>("username")?;
+        let pagination = req.query::<Pagination>()?;
 
-    let rows = query_as!(
-        UserResponse,
-        r#"
-            select users.id, users.username
-            from users
-            inner join follows on
-                follows.followee_id = $1
-                and follows.follower_id = users.id
-        "#,
-        user_id,
-    )
-    .fetch_all(&db_pool)
-    .await?;
+        let viewer_id = authenticate_if_present(&req).await?.map(|viewer| viewer.id);
+        let user_id = find_user(&db_pool, &username, viewer_id).await?.id;
 
-    rows.to_response()
+        let sql = format!(
+            r#"
+                select {}, {}
+                from users
+                inner join follows on
+                    follows.follower_id = $1
+                    and follows.followee_id = users.id
+                where users.deactivated_at is null
+                    and not exists (
+                        select 1 from blocks
+                        where (blocks.blocker_id = users.id and blocks.blocked_id = $2)
+                            or (blocks.blocker_id = $2 and blocks.blocked_id = users.id)
+                    )
+                order by follows.created_at desc
+                limit $3
+                offset $4
+            "#,
+            USER_COLUMNS,
+            RELATIONSHIP_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(user_id)
+            .bind(viewer_id)
+            .bind(pagination.limit())
+            .bind(pagination.offset())
+            .fetch_all(&db_pool)
+            .await?
+            .iter()
+            .map(user_with_relationship)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((rows, StatusCode::Ok))
+    }
 }
 
-async fn user_following(
-    current_user_id: Uuid,
-    followee_id: Uuid,
-    db_pool: &PgPool,) -> tide::Result<bool> {
-        let row = query!(
+#[async_trait]
+impl BackendApiEndpoint for Followers {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let db_pool = req.state().db_pool.clone();
+        let username = req.param::<String>("username")?;
+        let pagination = req.query::<Pagination>()?;
+
+        let viewer_id = authenticate_if_present(&req).await?.map(|viewer| viewer.id);
+        let user_id = find_user(&db_pool, &username, viewer_id).await?.id;
+
+        let sql = format!(
             r#"
-            select 1 as one from follows
-            where follower_id = $1 and followee_id = $2
-        "#,
-            current_user_id,
-            followee_id
-        )
-        .fetch_optional(db_pool)
-        .await?;
+                select {}, {}
+                from users
+                inner join follows on
+                    follows.followee_id = $1
+                    and follows.follower_id = users.id
+                where users.deactivated_at is null
+                    and not exists (
+                        select 1 from blocks
+                        where (blocks.blocker_id = users.id and blocks.blocked_id = $2)
+                            or (blocks.blocker_id = $2 and blocks.blocked_id = users.id)
+                    )
+                order by follows.created_at desc
+                limit $3
+                offset $4
+            "#,
+            USER_COLUMNS,
+            RELATIONSHIP_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(user_id)
+            .bind(viewer_id)
+            .bind(pagination.limit())
+            .bind(pagination.offset())
+            .fetch_all(&db_pool)
+            .await?
+            .iter()
+            .map(user_with_relationship)
+            .collect::<Result<Vec<_>, _>>()?;
 
-        Ok(row.is_some())
+        Ok((rows, StatusCode::Ok))
+    }
 }
 
 #[async_trait]
@@ -288,32 +523,90 @@ impl BackendApiEndpoint for GetUser {
     ) -> tide::Result<(UserResponse, StatusCode)> {
         let db_pool = &req.state().db_pool;
         let username = req.param::<String>("username")?;
+        let viewer_id = authenticate_if_present(&req).await?.map(|viewer| viewer.id);
 
-        let user = query_as!(
-            UserResponse,
-            r#"
-                select id, username
-                from users
-                where username = $1
-            "#,
-            username
-        )
+        let resp = load_user(db_pool, &username, viewer_id).await?;
+        Ok((resp, StatusCode::Ok))
+    }
+}
+
+/// Like `find_user`, but with everything a `UserResponse` needs.
+pub(crate) async fn load_user(
+    db_pool: &PgPool,
+    username: &str,
+    viewer_id: Option<Uuid>,
+) -> tide::Result<UserResponse> {
+    let sql = format!(
+        r#"
+            select {}, {}
+            from users
+            where lower(users.username) = lower($1)
+                and users.deactivated_at is null
+                and not exists (
+                    select 1 from blocks
+                    where blocks.blocker_id = users.id and blocks.blocked_id = $2
+                )
+        "#,
+        USER_COLUMNS,
+        RELATIONSHIP_COLUMNS
+    );
+    let user = sqlx::query(&sql)
+        .bind(username)
+        .bind(viewer_id)
         .fetch_optional(db_pool)
-        .await?;
+        .await?
+        .as_ref()
+        .map(user_with_relationship)
+        .transpose()?;
 
-        let resp = user.ok_or_else(|| Error::from_str(StatusCode::NotFound, "User not found"))?;
-        Ok((resp, StatusCode::Ok))
+    user.ok_or_else(user_not_found)
+}
+
+/// Users by id, for attaching to things that only store the id. Missing ids
+/// are simply absent from the map.
+pub(crate) async fn load_users_by_id(
+    db_pool: &PgPool,
+    ids: &[Uuid],
+) -> tide::Result<HashMap<Uuid, UserResponse>> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
     }
+
+    let sql = format!("select {} from users where users.id = any($1)", USER_COLUMNS);
+    let users = sqlx::query_as::<_, UserRow>(&sql)
+        .bind(ids)
+        .fetch_all(db_pool)
+        .await?
+        .into_iter()
+        .map(UserResponse::from);
+
+    Ok(users.map(|user| (user.id, user)).collect())
 }
 
-pub async fn logout(req: Request<State>) -> tide::Result {
-    let _ = authenticate(&req).await?;
-    let auth_token = get_auth_token(&req)?;
+#[async_trait]
+impl BackendApiEndpoint for Logout {
+    async fn handler(
+        req: Request<State>,
+        _: NoPayLoad,
+    ) -> tide::Result<(<Self as ApiEndpoint>::Response, StatusCode)> {
+        let user = authenticate(&req).await?;
+        // The session to end comes from the token, so a username that isn't
+        // the token's would sign out someone other than the URL says
+        let username = req.param::<String>("username")?;
+        if username.to_lowercase() != user.username.to_lowercase() {
+            return Err(api_error(
+                StatusCode::Forbidden,
+                ErrorCode::Forbidden,
+                "You can only sign yourself out",
+            ));
+        }
+        let token_hash = tokens::hash(get_auth_token(&req)?)?;
 
-    let db_pool = &req.state().db_pool;
-    query!("delete from auth_tokens where token = $1", auth_token)
-        .execute(db_pool)
-        .await?;
+        let db_pool = &req.state().db_pool;
+        query!("delete from auth_tokens where token_hash = $1", token_hash)
+            .execute(db_pool)
+            .await?;
 
-    empty_response()
+        Ok(((), StatusCode::Ok))
+    }
 }
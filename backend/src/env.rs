@@ -14,12 +14,13 @@ impl Env {
     }
 }
 
+/// Reads `APP_ENV`, which is development when unset. Anything unrecognised
+/// counts as production, so a typo errs on the side of the strict settings.
 pub fn current() -> Env {
-    match std::env::var("APP_ENV").unwrap().as_str() {
-        "production" => Env::Production,
-        "development" => Env::Development,
-        "test" => Env::Test,
-        _ => panic!(),
+    match std::env::var("APP_ENV").ok().as_deref() {
+        None | Some("development") => Env::Development,
+        Some("test") => Env::Test,
+        _ => Env::Production,
     }
 }
 
@@ -0,0 +1,29 @@
+use shared::errors::ErrorCode;
+use std::fmt;
+use tide::StatusCode;
+
+/// Carried inside a `tide::Error` when the status alone doesn't say enough,
+/// so `ErrorReponseToJson` can pass the code on to the client.
+#[derive(Debug)]
+pub struct CodedError {
+    pub code: ErrorCode,
+    message: String,
+}
+
+impl fmt::Display for CodedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CodedError {}
+
+pub fn api_error(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> tide::Error {
+    tide::Error::new(
+        status,
+        CodedError {
+            code,
+            message: message.into(),
+        },
+    )
+}
@@ -0,0 +1,106 @@
+//! Turns uploaded files into the images we serve. Everything is decoded and
+//! encoded again, which drops EXIF and any other metadata on the way, and
+//! cropped to fixed sizes so clients never get more pixels than they show.
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageOutputFormat};
+use std::io::Cursor;
+use thiserror::Error;
+
+/// Larger images are refused before decoding, to keep a small but highly
+/// compressed file from taking up gigabytes once decoded.
+const MAX_DIMENSION: u32 = 8000;
+
+const JPEG_QUALITY: u8 = 85;
+
+const ACCEPTED_TYPES: &[(&str, ImageFormat)] = &[
+    ("image/jpeg", ImageFormat::Jpeg),
+    ("image/png", ImageFormat::Png),
+    ("image/gif", ImageFormat::Gif),
+    ("image/webp", ImageFormat::WebP),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageKind {
+    Avatar,
+    Header,
+}
+
+impl ImageKind {
+    /// The renditions made of each upload, as name, width and height.
+    fn sizes(self) -> &'static [(&'static str, u32, u32)] {
+        match self {
+            ImageKind::Avatar => &[("full", 400, 400), ("thumbnail", 48, 48)],
+            ImageKind::Header => &[("full", 1500, 500)],
+        }
+    }
+
+    pub fn rendition_names(self) -> impl Iterator<Item = &'static str> {
+        self.sizes().iter().map(|&(name, _, _)| name)
+    }
+
+    pub fn key_prefix(self) -> &'static str {
+        match self {
+            ImageKind::Avatar => "avatars",
+            ImageKind::Header => "headers",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ImageError {
+    #[error("Images have to be JPEG, PNG, GIF or WebP")]
+    UnsupportedType,
+    #[error("File contents don't match its content type")]
+    TypeMismatch,
+    #[error("Image is too large (maximum is {max}x{max} pixels)", max = MAX_DIMENSION)]
+    TooManyPixels,
+    #[error("Image could not be read")]
+    Unreadable(#[from] image::ImageError),
+}
+
+/// One size of a processed upload, JPEG encoded.
+#[derive(Debug)]
+pub struct Rendition {
+    pub name: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+impl Rendition {
+    pub const CONTENT_TYPE: &'static str = "image/jpeg";
+    pub const EXTENSION: &'static str = "jpg";
+}
+
+/// Slow enough for big images that it should run on a blocking thread.
+pub fn process(kind: ImageKind, content_type: &str, bytes: &[u8]) -> Result<Vec<Rendition>, ImageError> {
+    let format = ACCEPTED_TYPES
+        .iter()
+        .find(|(accepted, _)| content_type.eq_ignore_ascii_case(accepted))
+        .map(|(_, format)| *format)
+        .ok_or(ImageError::UnsupportedType)?;
+
+    if image::guess_format(bytes).ok() != Some(format) {
+        return Err(ImageError::TypeMismatch);
+    }
+
+    let (width, height) = image::io::Reader::with_format(Cursor::new(bytes), format)
+        .into_dimensions()?;
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return Err(ImageError::TooManyPixels);
+    }
+
+    let image = image::load_from_memory_with_format(bytes, format)?;
+
+    kind.sizes()
+        .iter()
+        .map(|&(name, width, height)| {
+            let resized = image.resize_to_fill(width, height, FilterType::Lanczos3);
+            // JPEG has no alpha channel
+            let resized = DynamicImage::ImageRgb8(resized.to_rgb());
+
+            let mut bytes = Vec::new();
+            resized.write_to(&mut bytes, ImageOutputFormat::Jpeg(JPEG_QUALITY))?;
+            Ok(Rendition { name, bytes })
+        })
+        .collect()
+}
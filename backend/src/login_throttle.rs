@@ -0,0 +1,193 @@
+//! Slows down password guessing. Failed logins are recorded per username and
+//! per client IP. Past a threshold each further attempt has to wait twice as
+//! long as the last, and enough failures on one username lock it for a while.
+//!
+//! Usernames are tracked whether or not they exist, so the responses here
+//! don't reveal which accounts are real.
+
+use crate::config::Config;
+use chrono::{DateTime, Duration, Utc};
+use shared::errors::ErrorCode;
+use sqlx::{query, PgPool};
+use thiserror::Error;
+use tide::StatusCode;
+use uuid::Uuid;
+
+/// Carried inside a `tide::Error` so `ErrorReponseToJson` can add a
+/// `Retry-After` header.
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct RetryLater {
+    pub code: ErrorCode,
+    pub retry_after: Duration,
+    message: &'static str,
+}
+
+impl RetryLater {
+    /// Whole seconds, rounded up, as `Retry-After` wants them.
+    pub fn retry_after_seconds(&self) -> i64 {
+        let millis = self.retry_after.num_milliseconds();
+        ((millis + 999) / 1000).max(1)
+    }
+}
+
+struct Failures {
+    count: i64,
+    last_at: Option<DateTime<Utc>>,
+}
+
+/// Errors with a 429 if `username` or `ip_address` has to wait before trying
+/// again.
+pub async fn check(
+    db_pool: &PgPool,
+    config: &Config,
+    username: &str,
+    ip_address: Option<&str>,
+) -> tide::Result<()> {
+    let now = crate::clock::current_time().await;
+    let since = now - config.login_attempt_window;
+
+    let by_username = failures_for_username(db_pool, username, since).await?;
+
+    if let Some(last_at) = by_username.last_at {
+        if by_username.count >= config.login_lockout_threshold
+            && now < last_at + config.login_lockout_duration
+        {
+            return Err(retry_later(
+                ErrorCode::AccountLocked,
+                last_at + config.login_lockout_duration - now,
+                "Account is temporarily locked because of too many failed logins",
+            ));
+        }
+    }
+
+    let mut wait = backoff(config, &by_username, config.login_backoff_threshold, now);
+
+    if let Some(ip_address) = ip_address {
+        let by_ip = failures_for_ip(db_pool, ip_address, since).await?;
+        wait = wait.max(backoff(config, &by_ip, config.login_ip_backoff_threshold, now));
+    }
+
+    if wait > Duration::zero() {
+        return Err(retry_later(
+            ErrorCode::TooManyRequests,
+            wait,
+            "Too many failed logins, try again later",
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn record_failure(
+    db_pool: &PgPool,
+    config: &Config,
+    username: &str,
+    ip_address: Option<&str>,
+) -> tide::Result<()> {
+    let now = crate::clock::current_time().await;
+
+    query!(
+        "insert into failed_logins (id, username, ip_address, created_at) values ($1, $2, $3, $4)",
+        Uuid::new_v4(),
+        username,
+        ip_address,
+        now,
+    )
+    .execute(db_pool)
+    .await?;
+
+    query!(
+        "delete from failed_logins where created_at <= $1",
+        now - config.login_attempt_window,
+    )
+    .execute(db_pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Forgets earlier failures for `username` after it logs in successfully.
+/// Failures from the same IP against other usernames still count, otherwise
+/// logging into an account of your own would wipe the per-IP limit.
+pub async fn reset(db_pool: &PgPool, username: &str) -> tide::Result<()> {
+    query!(
+        "delete from failed_logins where lower(username) = lower($1)",
+        username,
+    )
+    .execute(db_pool)
+    .await?;
+
+    Ok(())
+}
+
+fn backoff(config: &Config, failures: &Failures, threshold: i64, now: DateTime<Utc>) -> Duration {
+    let last_at = match failures.last_at {
+        Some(last_at) if failures.count >= threshold => last_at,
+        _ => return Duration::zero(),
+    };
+
+    // Capping the exponent keeps the multiplication from overflowing, the
+    // delay itself is capped by `login_backoff_max` anyway
+    let exponent = (failures.count - threshold).min(20) as u32;
+    let delay = (config.login_backoff_base * 2i32.pow(exponent)).min(config.login_backoff_max);
+
+    (last_at + delay - now).max(Duration::zero())
+}
+
+fn retry_later(code: ErrorCode, retry_after: Duration, message: &'static str) -> tide::Error {
+    tide::Error::new(
+        StatusCode::TooManyRequests,
+        RetryLater {
+            code,
+            retry_after,
+            message,
+        },
+    )
+}
+
+async fn failures_for_username(
+    db_pool: &PgPool,
+    username: &str,
+    since: DateTime<Utc>,
+) -> tide::Result<Failures> {
+    let row = query!(
+        r#"
+            select count(*) as count, max(created_at) as last_at
+            from failed_logins
+            where lower(username) = lower($1) and created_at > $2
+        "#,
+        username,
+        since,
+    )
+    .fetch_one(db_pool)
+    .await?;
+
+    Ok(Failures {
+        count: row.count.unwrap_or(0),
+        last_at: row.last_at,
+    })
+}
+
+async fn failures_for_ip(
+    db_pool: &PgPool,
+    ip_address: &str,
+    since: DateTime<Utc>,
+) -> tide::Result<Failures> {
+    let row = query!(
+        r#"
+            select count(*) as count, max(created_at) as last_at
+            from failed_logins
+            where ip_address = $1 and created_at > $2
+        "#,
+        ip_address,
+        since,
+    )
+    .fetch_one(db_pool)
+    .await?;
+
+    Ok(Failures {
+        count: row.count.unwrap_or(0),
+        last_at: row.last_at,
+    })
+}
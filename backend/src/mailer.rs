@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, Error)]
+pub enum MailerError {
+    #[error("failed to write email: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to encode email: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to send email: {0}")]
+    Smtp(String),
+}
+
+#[async_trait]
+pub trait Mailer: Debug + Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), MailerError>;
+}
+
+/// Picks a mailer based on `MAILER`. `smtp` sends real email, anything else
+/// writes it to `MAIL_DIR` (default `tmp/mail`).
+pub fn from_env() -> Arc<dyn Mailer> {
+    let from = std::env::var("MAIL_FROM").unwrap_or_else(|_| "witter@localhost".to_string());
+
+    match std::env::var("MAILER").ok().as_deref() {
+        Some("smtp") => Arc::new(SmtpMailer {
+            host: std::env::var("SMTP_HOST").expect("SMTP_HOST missing from environment"),
+            username: std::env::var("SMTP_USERNAME").ok(),
+            password: std::env::var("SMTP_PASSWORD").ok(),
+            from,
+        }),
+        _ => {
+            let dir = std::env::var("MAIL_DIR").unwrap_or_else(|_| "tmp/mail".to_string());
+            Arc::new(FileMailer::new(dir))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SmtpMailer {
+    host: String,
+    username: Option<String>,
+    password: Option<String>,
+    from: String,
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        use lettre::smtp::authentication::Credentials;
+        use lettre::{SmtpClient, Transport};
+        use lettre_email::EmailBuilder;
+
+        let message = EmailBuilder::new()
+            .to(email.to)
+            .from(self.from.clone())
+            .subject(email.subject)
+            .text(email.body)
+            .build()
+            .map_err(|err| MailerError::Smtp(err.to_string()))?;
+
+        let mut client =
+            SmtpClient::new_simple(&self.host).map_err(|err| MailerError::Smtp(err.to_string()))?;
+        if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            client = client.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        // lettre is blocking, so keep it off the executor threads
+        async_std::task::spawn_blocking(move || {
+            client
+                .transport()
+                .send(message.into())
+                .map(|_| ())
+                .map_err(|err| MailerError::Smtp(err.to_string()))
+        })
+        .await
+    }
+}
+
+/// Writes each email to its own JSON file instead of sending it. Used in
+/// development and tests, where `sent` reads them back.
+#[derive(Debug)]
+pub struct FileMailer {
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    /// Every email written so far, oldest first.
+    pub async fn sent(&self) -> Result<Vec<Email>, MailerError> {
+        use futures::stream::StreamExt;
+
+        if !async_std::path::Path::new(&self.dir).exists().await {
+            return Ok(Vec::new());
+        }
+
+        let mut paths = Vec::new();
+        let mut entries = async_std::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next().await {
+            let path = entry?.path();
+            if path.extension().map_or(false, |extension| extension == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut emails = Vec::new();
+        for path in paths {
+            let contents = async_std::fs::read_to_string(path).await?;
+            emails.push(serde_json::from_str(&contents)?);
+        }
+        Ok(emails)
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> Result<(), MailerError> {
+        async_std::fs::create_dir_all(&self.dir).await?;
+
+        // Wall clock rather than `clock::current_time` so files still sort in
+        // the order they were written when time is frozen
+        let now = chrono::Utc::now();
+        let file_name = format!("{}-{}.json", now.format("%Y%m%d%H%M%S%f"), Uuid::new_v4());
+        let path = self.dir.join(file_name);
+
+        log::info!("writing email to {:?} for {}: {}", path, email.to, email.subject);
+        // Written under another name first so `sent` never reads half an email
+        let tmp_path = path.with_extension("tmp");
+        async_std::fs::write(&tmp_path, serde_json::to_string_pretty(&email)?).await?;
+        async_std::fs::rename(tmp_path, path).await?;
+        Ok(())
+    }
+}
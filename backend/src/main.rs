@@ -6,38 +6,112 @@ use payloads::*;
 use shared::*;
 use sqlx::PgPool;
 use sqlx::Pool;
+use std::sync::Arc;
 use tide::http::{headers::HeaderValue, Method};
 use tide::security::CorsMiddleware;
 use tide::security::Origin;
-use tide::{Body, Request, Response, Server, StatusCode};
+use tide::{Body, Endpoint, Request, Response, Server, StatusCode};
 
 #[cfg(test)]
 mod tests;
 
+mod accounts;
+mod blobs;
+mod config;
 mod endpoints;
 mod env;
+mod errors;
+mod images;
+mod login_throttle;
+mod mailer;
 mod middlewares;
-mod responses;
+mod migrations;
+mod passwords;
+mod routes;
+mod tokens;
+mod validation;
 mod clock;
 
+/// Without a command the server starts.
+const USAGE: &str = "\
+Usage: backend [COMMAND]
+
+Commands:
+    migrate           Apply pending migrations
+    purge             Purge accounts whose deletion grace period is over
+    rollback [STEPS]  Revert the last STEPS migrations (default 1)";
+
 #[async_std::main]
 async fn main() {
     dotenv::dotenv().ok();
     pretty_env_logger::try_init().ok();
 
     let db_pool = make_db_pool().await;
-    let app = server(db_pool).await;
+    let config = config::Config::from_env();
+    let blobs = blobs::from_env();
+
+    match std_env::args().nth(1).as_deref() {
+        Some("migrate") => return run_migrations(&db_pool).await,
+        Some("purge") => {
+            let purged = accounts::purge_expired(&db_pool, &config, &*blobs)
+                .await
+                .unwrap_or_else(|err| panic!("Failed to purge deleted accounts: {}", err));
+            println!("Purged {} deleted accounts", purged);
+            return;
+        }
+        Some("rollback") => {
+            let steps = std_env::args()
+                .nth(2)
+                .map(|steps| steps.parse().expect("rollback steps must be a number"))
+                .unwrap_or(1);
+            let reverted = migrations::rollback(&db_pool, steps)
+                .await
+                .unwrap_or_else(|err| panic!("Failed to roll back migrations: {}", err));
+            for migration in reverted {
+                println!("Reverted {}", migration.name);
+            }
+            return;
+        }
+        Some(command) => {
+            eprintln!("Unknown command `{}`\n\n{}", command, USAGE);
+            std::process::exit(2);
+        }
+        None => {}
+    }
+
+    if std_env::var("RUN_MIGRATIONS").ok().as_deref() == Some("true") {
+        run_migrations(&db_pool).await;
+    }
+
+    accounts::spawn_purger(db_pool.clone(), config.clone(), blobs.clone());
+
+    let state = State {
+        db_pool,
+        config,
+        mailer: mailer::from_env(),
+        blobs,
+    };
+    let app = server(state).await;
 
     app.listen("127.0.0.1:8080").await.unwrap();
 }
 
 pub async fn make_db_pool() -> PgPool {
-    let db_url = std_env::var("DATABASE_URL").unwrap();
-    Pool::new(&db_url).await.unwrap()
+    let db_url = std_env::var("DATABASE_URL").expect("DATABASE_URL missing from environment");
+    // The URL is left out of the message, it usually contains a password
+    Pool::new(&db_url)
+        .await
+        .unwrap_or_else(|err| panic!("Failed to connect to the database: {}", err))
+}
+
+async fn run_migrations(db_pool: &PgPool) {
+    for migration in migrations::migrate(db_pool).await.unwrap() {
+        println!("Applied {}", migration.name);
+    }
 }
 
-async fn server(db_pool: PgPool) -> Server<State> {
-    let mut server: Server<State> = Server::with_state(State { db_pool });
+async fn server(state: State) -> Server<State> {
+    let mut server: Server<State> = Server::with_state(state);
     
     server.with(
         CorsMiddleware::new()
@@ -49,31 +123,58 @@ async fn server(db_pool: PgPool) -> Server<State> {
             .allow_origin(Origin::Any),
     );
     server.with(middlewares::ErrorReponseToJson);
+    server.with(middlewares::CatchPanics);
 
-//    server.at("/users").post(endpoints::users::create);
     add_endpoint::<CreateUser>(&mut server);
 
     add_endpoint::<Login>(&mut server);
+    add_endpoint::<RequestPasswordReset>(&mut server);
+    add_endpoint::<ConfirmPasswordReset>(&mut server);
 
-    server
-        .at("/users/:username/session")
-        .delete(endpoints::users::logout);
-    server
-        .at("/users/:username/follow")
-        .post(endpoints::users::follow);
-    server
-        .at("/users/:username/following")
-        .get(endpoints::users::following);
-    server
-        .at("/users/:username/followers")
-        .get(endpoints::users::followers);
+    add_endpoint::<Logout>(&mut server);
+    add_endpoint::<Follow>(&mut server);
+    add_endpoint::<Unfollow>(&mut server);
+    add_endpoint::<Block>(&mut server);
+    add_endpoint::<Unblock>(&mut server);
+    add_endpoint::<MuteUser>(&mut server);
+    add_endpoint::<UnmuteUser>(&mut server);
+    add_endpoint::<MutedUsers>(&mut server);
+    add_endpoint::<MuteKeyword>(&mut server);
+    add_endpoint::<MutedKeywords>(&mut server);
+    add_endpoint::<UnmuteKeyword>(&mut server);
+    add_endpoint::<FollowRequests>(&mut server);
+    add_endpoint::<ApproveFollowRequest>(&mut server);
+    add_endpoint::<RejectFollowRequest>(&mut server);
+    add_endpoint::<Following>(&mut server);
+    add_endpoint::<Followers>(&mut server);
 
     add_endpoint::<GetUser>(&mut server);
 
     add_endpoint::<Me>(&mut server);
+    add_endpoint::<UpdateProfile>(&mut server);
+    add_endpoint::<UploadAvatar>(&mut server);
+    add_endpoint::<UploadHeader>(&mut server);
     add_endpoint::<Timeline>(&mut server);
+    add_endpoint::<ChangePassword>(&mut server);
+    add_endpoint::<DeleteAccount>(&mut server);
+    add_raw_endpoint::<ExportData>(&mut server, endpoints::export::export);
+    add_endpoint::<Sessions>(&mut server);
+    add_endpoint::<RevokeSession>(&mut server);
 
     add_endpoint::<PostTweet>(&mut server);
+    add_endpoint::<UserTweets>(&mut server);
+    add_endpoint::<UserLikes>(&mut server);
+    add_endpoint::<GetTweet>(&mut server);
+    add_endpoint::<DeleteTweet>(&mut server);
+    add_endpoint::<EditTweet>(&mut server);
+    add_endpoint::<TweetHistory>(&mut server);
+    add_endpoint::<Conversation>(&mut server);
+    add_endpoint::<Retweet>(&mut server);
+    add_endpoint::<Unretweet>(&mut server);
+    add_endpoint::<Like>(&mut server);
+    add_endpoint::<Unlike>(&mut server);
+
+    add_raw_endpoint::<GetBlob>(&mut server, endpoints::uploads::serve_blob);
 
     server
 }
@@ -81,6 +182,9 @@ async fn server(db_pool: PgPool) -> Server<State> {
 #[derive(Debug, Clone)]
 pub struct State {
     db_pool: PgPool,
+    config: config::Config,
+    mailer: Arc<dyn mailer::Mailer>,
+    blobs: Arc<dyn blobs::BlobStore>,
 }
 
 // let's use async_trait crate, which implements Box on traits
@@ -117,8 +221,16 @@ macro_rules! impl_get_request_payload {
 }
 
 impl_get_request_payload!(CreateTweetPayload);
+impl_get_request_payload!(EditTweetPayload);
 impl_get_request_payload!(LoginPayload);
 impl_get_request_payload!(CreateUserPayload);
+impl_get_request_payload!(ChangePasswordPayload);
+impl_get_request_payload!(RequestPasswordResetPayload);
+impl_get_request_payload!(ConfirmPasswordResetPayload);
+impl_get_request_payload!(DeleteAccountPayload);
+impl_get_request_payload!(UpdateProfilePayload);
+impl_get_request_payload!(MuteUserPayload);
+impl_get_request_payload!(MuteKeywordPayload);
 
 
 fn add_endpoint<E>(server: &mut Server<State>) 
@@ -126,7 +238,9 @@ where
     E: 'static + BackendApiEndpoint,
     E::Payload: GetRequestPayload + Send,
 { 
-    let mut route = server.at(<E::Url as shared::Url>::URL_SPEC);
+    let url_spec = <E::Url as shared::Url>::URL_SPEC;
+    routes::record(E::METHOD, url_spec);
+    let mut route = server.at(url_spec);
     
     let handler = |mut req: Request<State> | async {
         let payload = E::Payload::get_payload(&mut req).await?;
@@ -149,3 +263,26 @@ where
         Method::Patch => route.patch(handler),
     };
 }
+
+/// For endpoints that don't answer with JSON, like downloads. The handler
+/// builds the whole response itself, the route still comes from `E`.
+fn add_raw_endpoint<E>(server: &mut Server<State>, handler: impl Endpoint<State>)
+where
+    E: ApiEndpoint,
+{
+    let url_spec = <E::Url as shared::Url>::URL_SPEC;
+    routes::record(E::METHOD, url_spec);
+    let mut route = server.at(url_spec);
+
+    match E::METHOD {
+        Method::Get => route.get(handler),
+        Method::Post => route.post(handler),
+        Method::Head => route.head(handler),
+        Method::Put => route.put(handler),
+        Method::Delete => route.delete(handler),
+        Method::Connect => route.connect(handler),
+        Method::Options => route.options(handler),
+        Method::Trace => route.trace(handler),
+        Method::Patch => route.patch(handler),
+    };
+}
@@ -1,6 +1,13 @@
+use crate::errors::{api_error, CodedError};
+use crate::login_throttle::RetryLater;
+use crate::validation::FieldErrors;
 // use crate::State;
 // use futures::future::BoxFuture;
+use futures::FutureExt;
 use serde_json::json;
+use shared::errors::{ApiError, ErrorCode};
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
 // use std::future::Future;
 // use std::pin::Pin;
 // use tide::http::headers::HeaderValue;
@@ -10,6 +17,7 @@ use tide::Middleware;
 use tide::Next;
 use tide::Request;
 use tide::Response;
+use tide::StatusCode;
 
 
 #[derive(Debug)]
@@ -22,14 +30,21 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for ErrorReponseToJ
 
         if let Some(err) = resp.error() {
             let status = err.status();
-            let body = json!({
-                "error": {
-                    "status_code": status.to_string(),
-                    "message": format!("{}", err),
-                }
-            });
+            let error = ApiError {
+                status_code: status.to_string(),
+                code: error_code(err),
+                message: format!("{}", err),
+                errors: err.downcast_ref::<FieldErrors>().map(FieldErrors::to_map),
+            };
+            let retry_after = err
+                .downcast_ref::<RetryLater>()
+                .map(|err| err.retry_after_seconds());
+
             let mut resp = Response::new(status);
-            resp.set_body(body);
+            resp.set_body(json!({ "error": error }));
+            if let Some(seconds) = retry_after {
+                resp.insert_header("Retry-After", seconds.to_string());
+            }
             Ok(resp)
         } else {
             let status = resp.status();
@@ -39,14 +54,15 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for ErrorReponseToJ
             } else {
                 let body = resp.take_body();
 
-                if body.is_empty().expect("no length on response body") {
-                    let new_body = json!({
-                        "error": {
-                            "status_code": status.to_string(),
-                            "message": "Something went wrong",
-                        }
-                    });
-                    resp.set_body(new_body);
+                // Streamed bodies don't know their length, those have content
+                if body.is_empty().unwrap_or(false) {
+                    let error = ApiError {
+                        status_code: status.to_string(),
+                        code: ErrorCode::from_status(status as u16),
+                        message: "Something went wrong".to_string(),
+                        errors: None,
+                    };
+                    resp.set_body(json!({ "error": error }));
                 } else {
                     resp.set_body(body);
                 }
@@ -55,4 +71,57 @@ impl<State: Clone + Send + Sync + 'static> Middleware<State> for ErrorReponseToJ
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Turns a panic in a handler into a 500, so the client still gets an
+/// error body instead of a dropped connection. Has to be added after
+/// `ErrorReponseToJson` for that body to be JSON.
+#[derive(Debug)]
+pub struct CatchPanics;
+
+#[async_trait::async_trait]
+impl<State: Clone + Send + Sync + 'static> Middleware<State> for CatchPanics {
+    async fn handle(&self, req: Request<State>, next: Next<'_, State>) -> tide::Result {
+        let method = req.method();
+        let path = req.url().path().to_string();
+
+        match AssertUnwindSafe(next.run(req)).catch_unwind().await {
+            Ok(resp) => Ok(resp),
+            Err(panic) => {
+                log::error!(
+                    "panic while handling {} {}: {}",
+                    method,
+                    path,
+                    panic_message(&*panic)
+                );
+                Err(api_error(
+                    StatusCode::InternalServerError,
+                    ErrorCode::InternalError,
+                    "Something went wrong",
+                ))
+            }
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown cause"
+    }
+}
+
+fn error_code(err: &tide::Error) -> ErrorCode {
+    if let Some(err) = err.downcast_ref::<CodedError>() {
+        err.code
+    } else if let Some(err) = err.downcast_ref::<FieldErrors>() {
+        err.code()
+    } else if let Some(err) = err.downcast_ref::<RetryLater>() {
+        err.code
+    } else {
+        ErrorCode::from_status(err.status() as u16)
+    }
+}
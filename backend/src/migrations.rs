@@ -0,0 +1,205 @@
+use sqlx::prelude::*;
+use sqlx::PgPool;
+use thiserror::Error;
+
+/// A single schema change. The SQL is embedded at compile time so the
+/// binary always carries the migrations it was built against.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    up: &'static str,
+    down: &'static str,
+}
+
+macro_rules! migration {
+    ($version: expr, $file: literal) => {
+        Migration {
+            version: $version,
+            name: $file,
+            up: include_str!(concat!("../migrations/", $file, ".up.sql")),
+            down: include_str!(concat!("../migrations/", $file, ".down.sql")),
+        }
+    };
+}
+
+/// Every migration, in the order it must be applied. New files go in
+/// `backend/migrations` as `NNNN_name.up.sql` / `NNNN_name.down.sql` and
+/// get a line here.
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_initial_schema"),
+    migration!(2, "0002_sessions"),
+    migration!(3, "0003_hash_auth_tokens"),
+    migration!(4, "0004_password_resets"),
+    migration!(5, "0005_failed_logins"),
+    migration!(6, "0006_case_insensitive_usernames"),
+    migration!(7, "0007_account_deletion"),
+    migration!(8, "0008_user_profiles"),
+    migration!(9, "0009_profile_images"),
+    migration!(10, "0010_protected_accounts"),
+    migration!(11, "0011_blocks"),
+    migration!(12, "0012_mutes"),
+    migration!(13, "0013_user_counters"),
+    migration!(14, "0014_tweet_revisions"),
+    migration!(15, "0015_replies"),
+    migration!(16, "0016_retweets"),
+    migration!(17, "0017_likes"),
+    migration!(18, "0018_counts_skip_deactivated_users"),
+];
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("migration {version} ({name}) failed: {source}")]
+    Failed {
+        version: i64,
+        name: &'static str,
+        source: sqlx::Error,
+    },
+    #[error("database is at version {0}, which this build doesn't know about")]
+    UnknownVersion(i64),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Applies every pending migration, each in its own transaction, and returns
+/// the ones that ran.
+///
+/// Postgres DDL is transactional, so if two processes race here the loser's
+/// insert into `schema_migrations` fails and its copy of the migration is
+/// rolled back.
+pub async fn migrate(db_pool: &PgPool) -> Result<Vec<Migration>, MigrationError> {
+    ensure_migrations_table(db_pool).await?;
+    let applied = applied_versions(db_pool).await?;
+
+    if let Some(version) = applied
+        .iter()
+        .find(|version| !MIGRATIONS.iter().any(|m| m.version == **version))
+    {
+        return Err(MigrationError::UnknownVersion(*version));
+    }
+
+    let mut ran = Vec::new();
+    for migration in MIGRATIONS {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+
+        log::info!("applying migration {}", migration.name);
+        apply(db_pool, migration, migration.up, Direction::Up)
+            .await
+            .map_err(|source| MigrationError::Failed {
+                version: migration.version,
+                name: migration.name,
+                source,
+            })?;
+        ran.push(*migration);
+    }
+
+    Ok(ran)
+}
+
+/// Reverts the `steps` most recently applied migrations, newest first.
+pub async fn rollback(db_pool: &PgPool, steps: usize) -> Result<Vec<Migration>, MigrationError> {
+    ensure_migrations_table(db_pool).await?;
+    let mut applied = applied_versions(db_pool).await?;
+    applied.sort();
+
+    let mut reverted = Vec::new();
+    for version in applied.into_iter().rev().take(steps) {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|m| m.version == version)
+            .ok_or(MigrationError::UnknownVersion(version))?;
+
+        log::info!("reverting migration {}", migration.name);
+        apply(db_pool, migration, migration.down, Direction::Down)
+            .await
+            .map_err(|source| MigrationError::Failed {
+                version: migration.version,
+                name: migration.name,
+                source,
+            })?;
+        reverted.push(*migration);
+    }
+
+    Ok(reverted)
+}
+
+/// Versions recorded in `schema_migrations`, in no particular order.
+pub async fn applied_versions(db_pool: &PgPool) -> Result<Vec<i64>, sqlx::Error> {
+    let rows = sqlx::query("select version from schema_migrations")
+        .fetch_all(db_pool)
+        .await?;
+
+    Ok(rows.iter().map(|row| row.get::<i64, _>("version")).collect())
+}
+
+enum Direction {
+    Up,
+    Down,
+}
+
+async fn apply(
+    db_pool: &PgPool,
+    migration: &Migration,
+    sql: &str,
+    direction: Direction,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db_pool.begin().await?;
+
+    // Lets data migrations that need to hash things do so with the same key
+    // the backend uses. `true` scopes it to this transaction.
+    if let Ok(secret_key) = std::env::var("SECRET_KEY") {
+        sqlx::query("select set_config('witter.secret_key', $1, true)")
+            .bind(secret_key)
+            .execute(&mut tx)
+            .await?;
+    }
+
+    // Same for data migrations that backfill session expiry times, so they
+    // match the TTL new sessions get.
+    let session_ttl = crate::config::Config::from_env().session_ttl;
+    sqlx::query("select set_config('witter.session_ttl_seconds', $1, true)")
+        .bind(session_ttl.num_seconds().to_string())
+        .execute(&mut tx)
+        .await?;
+
+    // A plain `&str` goes over the simple query protocol, which accepts
+    // several statements at once. That lets Postgres do the parsing, so
+    // semicolons inside functions and string literals are fine.
+    tx.execute(sql).await?;
+
+    match direction {
+        Direction::Up => {
+            sqlx::query("insert into schema_migrations (version, name) values ($1, $2)")
+                .bind(migration.version)
+                .bind(migration.name)
+                .execute(&mut tx)
+                .await?;
+        }
+        Direction::Down => {
+            sqlx::query("delete from schema_migrations where version = $1")
+                .bind(migration.version)
+                .execute(&mut tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn ensure_migrations_table(db_pool: &PgPool) -> Result<(), sqlx::Error> {
+    let mut conn = db_pool.acquire().await?;
+    conn.execute(
+        r#"
+            create table if not exists schema_migrations (
+                version bigint primary key,
+                name varchar not null,
+                applied_at timestamp with time zone not null default now()
+            )
+        "#,
+    )
+    .await?;
+    Ok(())
+}
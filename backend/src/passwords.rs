@@ -0,0 +1,63 @@
+use crate::{env, tokens};
+use argonautica::{Hasher, Verifier};
+use async_std::sync::Mutex;
+use failure::Fail;
+use futures::compat::Compat01As03;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref DUMMY_HASH: Mutex<Option<String>> = Mutex::new(None);
+}
+
+pub async fn hash(clear_text_password: &str) -> tide::Result<String> {
+    let secret_key = std::env::var("SECRET_KEY")?;
+    let mut hasher = Hasher::default();
+
+    if env::current().is_test() {
+        hasher.configure_iterations(1);
+    }
+
+    let hashed_password = Compat01As03::new(
+        hasher
+            .with_password(clear_text_password.to_string())
+            .with_secret_key(secret_key)
+            .hash_non_blocking(),
+    )
+    .await
+    .map_err(|err| err.compat())?;
+
+    Ok(hashed_password)
+}
+
+pub async fn verify(hashed_password: &str, clear_text_password: &str) -> tide::Result<bool> {
+    let secret_key = std::env::var("SECRET_KEY")?;
+    let mut verifier = Verifier::default();
+
+    let is_valid = Compat01As03::new(
+        verifier
+            .with_hash(hashed_password.to_string())
+            .with_password(clear_text_password.to_string())
+            .with_secret_key(secret_key)
+            .verify_non_blocking(),
+    )
+    .await
+    .map_err(|err| err.compat())?;
+
+    Ok(is_valid)
+}
+
+/// Does the same work as `verify` against a throwaway hash. Used when there's
+/// no user to check against, so the response takes as long as it would for a
+/// real one.
+pub async fn verify_dummy(clear_text_password: &str) -> tide::Result<()> {
+    let dummy_hash = {
+        let mut dummy_hash = DUMMY_HASH.lock().await;
+        if dummy_hash.is_none() {
+            *dummy_hash = Some(hash(&tokens::generate()).await?);
+        }
+        dummy_hash.clone().unwrap()
+    };
+
+    verify(&dummy_hash, clear_text_password).await?;
+    Ok(())
+}
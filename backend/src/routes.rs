@@ -0,0 +1,59 @@
+//! Every route registered through `add_endpoint` and `add_raw_endpoint`.
+//! Tests check each request they make against this list, so a route added
+//! with a bare `Server::at` fails the suite as soon as a test calls it.
+
+use lazy_static::lazy_static;
+use std::sync::Mutex;
+use tide::http::Method;
+
+lazy_static! {
+    static ref ROUTES: Mutex<Vec<(Method, &'static str)>> = Mutex::new(Vec::new());
+}
+
+pub fn record(method: Method, url_spec: &'static str) {
+    let mut routes = ROUTES.lock().unwrap();
+    if !routes.contains(&(method, url_spec)) {
+        routes.push((method, url_spec));
+    }
+}
+
+/// Everything recorded so far, in the order it was added.
+#[cfg(test)]
+pub fn recorded() -> Vec<(Method, &'static str)> {
+    ROUTES.lock().unwrap().clone()
+}
+
+/// Whether a recorded route answers `method` requests for `path`.
+#[cfg(test)]
+pub fn is_registered(method: Method, path: &str) -> bool {
+    ROUTES
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(route_method, url_spec)| *route_method == method && matches(url_spec, path))
+}
+
+/// Follows tide's syntax, `:name` matches one segment and `*name` the rest.
+#[cfg(test)]
+fn matches(url_spec: &str, path: &str) -> bool {
+    let mut spec_segments = url_spec.trim_start_matches('/').split('/');
+    let mut path_segments = path.trim_start_matches('/').split('/');
+
+    loop {
+        match (spec_segments.next(), path_segments.next()) {
+            (None, None) => return true,
+            (Some(spec), Some(segment)) if spec.starts_with('*') => return !segment.is_empty(),
+            (Some(spec), Some(segment)) if spec.starts_with(':') => {
+                if segment.is_empty() {
+                    return false;
+                }
+            }
+            (Some(spec), Some(segment)) => {
+                if spec != segment {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+}
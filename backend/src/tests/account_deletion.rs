@@ -0,0 +1,150 @@
+use crate::accounts;
+use crate::clock::*;
+use crate::config::Config;
+use crate::tests::test_helpers::*;
+use chrono::Duration;
+use sqlx::prelude::*;
+
+async fn delete_account(password: &str, token: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = delete_with_body(
+        "/me",
+        Some(DeleteAccountPayload {
+            password: password.to_string(),
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(server)
+    .await;
+    (json, status)
+}
+
+async fn count(sql: &str, server: &TestServer) -> i64 {
+    let row = sqlx::query(sql).fetch_one(&server.db_pool()).await.unwrap();
+    row.get(0)
+}
+
+#[async_std::test]
+async fn deleting_requires_the_password() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status) = delete_account("wrong", &token, &server).await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "incorrect_password",
+            }
+        })
+    );
+
+    let (_, status, _) = get("/users/bob").send(&server).await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn deleted_accounts_are_hidden_and_signed_out() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status) = delete_account("foobar", &token, &server).await;
+    assert_eq!(status, 202);
+    assert!(json["data"]["purge_at"].is_string());
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 401);
+
+    let (_, status, _) = get("/users/bob").send(&server).await;
+    assert_eq!(status, 404);
+}
+
+#[async_std::test]
+async fn logging_in_during_the_grace_period_restores_the_account() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    delete_account("foobar", &token, &server).await;
+
+    let (_, status, _) = post(
+        "/users/bob/session",
+        Some(LoginPayload {
+            password: "foobar".to_string(),
+        }),
+    )
+    .send(&server)
+    .await;
+    assert_eq!(status, 201);
+
+    let (_, status, _) = get("/users/bob").send(&server).await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn purging_removes_tweets_follows_and_sessions() {
+    let server = test_setup_with_config(|config| {
+        config.account_deletion_grace_period = Duration::zero()
+    })
+    .await;
+
+    let bobs_token = token_for("bob", &server).await;
+    let alices_token = token_for("alice", &server).await;
+
+    tweet("Hello", &bobs_token, &server).await;
+    follow("alice", &bobs_token, &server).await;
+    follow("bob", &alices_token, &server).await;
+
+    let (json, status) = delete_account("foobar", &bobs_token, &server).await;
+    assert_eq!(status, 200);
+    assert_json_eq!(json, json!({ "data": { "purge_at": null } }));
+
+    assert_eq!(count("select count(*) from users", &server).await, 1);
+    assert_eq!(count("select count(*) from tweets", &server).await, 0);
+    assert_eq!(count("select count(*) from follows", &server).await, 0);
+    assert_eq!(count("select count(*) from auth_tokens", &server).await, 1);
+
+    let (_, status, _) = get("/me/timeline")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn accounts_are_purged_after_the_grace_period() {
+    let server = test_setup().await;
+    let config = Config::from_env();
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    freeze_time::<(), _, _>(start(), || async {
+        delete_account("foobar", &token, &server).await;
+    })
+    .await;
+
+    let purged = freeze_time::<u64, _, _>(start() + Duration::days(13), || async {
+        accounts::purge_expired(&server.db_pool(), &config, server.blobs())
+            .await
+            .unwrap()
+    })
+    .await;
+    assert_eq!(purged, 0);
+
+    let purged = freeze_time::<u64, _, _>(start() + Duration::days(15), || async {
+        accounts::purge_expired(&server.db_pool(), &config, server.blobs())
+            .await
+            .unwrap()
+    })
+    .await;
+    assert_eq!(purged, 1);
+    assert_eq!(count("select count(*) from users", &server).await, 0);
+}
@@ -0,0 +1,199 @@
+use crate::tests::test_helpers::*;
+
+async fn block(username: &str, token: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = empty_post(&format!("/users/{}/block", username))
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    (json, status)
+}
+
+#[async_std::test]
+async fn blocking_ends_follows_both_ways() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    follow("alice", &bobs_token, &server).await;
+    follow("bob", &alices_token, &server).await;
+
+    let (json, status) = block("bob", &alices_token, &server).await;
+    assert_eq!(status, 201);
+    assert_json_eq!(json, json!({ "data": null }));
+
+    let (_, status) = block("bob", &alices_token, &server).await;
+    assert_eq!(status, 200);
+
+    for url in &[
+        "/users/alice/followers",
+        "/users/alice/following",
+        "/users/bob/followers",
+        "/users/bob/following",
+    ] {
+        let (json, _, _) = get(url).send(&server).await;
+        assert_json_eq!(json, json!({ "data": [] }));
+    }
+
+    // Unblocking doesn't bring the follows back
+    let (_, status, _) = delete("/users/bob/block")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    let (json, _, _) = get("/users/alice/followers").send(&server).await;
+    assert_json_eq!(json, json!({ "data": [] }));
+}
+
+#[async_std::test]
+async fn blocked_users_cannot_follow_again() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    block("bob", &alices_token, &server).await;
+
+    let (json, status) = follow("alice", &bobs_token, &server).await;
+    assert_eq!(status, 404);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "user_not_found",
+            }
+        })
+    );
+
+    let (json, status) = follow("bob", &alices_token, &server).await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "user_blocked",
+            }
+        })
+    );
+
+    delete("/users/bob/block")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+
+    let (_, status) = follow("alice", &bobs_token, &server).await;
+    assert_eq!(status, 201);
+}
+
+#[async_std::test]
+async fn blockers_are_hidden_from_blocked_users() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    let carols_token = create_user_and_authenticate(&server, Some("carol".to_string()))
+        .await
+        .token;
+    follow("carol", &alices_token, &server).await;
+    follow("carol", &bobs_token, &server).await;
+    block("bob", &alices_token, &server).await;
+
+    for url in &["/users/alice", "/users/alice/followers", "/users/alice/tweets"] {
+        let (_, status, _) = get(url)
+            .header("Authorization", format!("Bearer {}", bobs_token))
+            .send(&server)
+            .await;
+        assert_eq!(status, 404);
+
+        let (_, status, _) = get(url).send(&server).await;
+        assert_eq!(status, 200);
+    }
+
+    let (json, _, _) = get("/users/carol/followers")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_json_include!(actual: json, expected: json!({ "data": [{ "username": "bob" }] }));
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+
+    let (json, _, _) = get("/users/carol/followers")
+        .header("Authorization", format!("Bearer {}", carols_token))
+        .send(&server)
+        .await;
+    assert_eq!(json["data"].as_array().unwrap().len(), 2);
+}
+
+#[async_std::test]
+async fn blockers_cannot_interact_with_blocked_users_tweets() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    let tweet_id = tweet("Hello", &bobs_token, &server).await;
+    block("bob", &alices_token, &server).await;
+
+    let (_, status) = post_tweet(
+        CreateTweetPayload {
+            text: "Reply".to_string(),
+            in_reply_to_id: Some(tweet_id.parse().unwrap()),
+            ..Default::default()
+        },
+        &alices_token,
+        &server,
+    )
+    .await;
+    assert_eq!(status, 404);
+
+    for action in &["like", "retweet"] {
+        let (_, status, _) = empty_post(&format!("/tweets/{}/{}", tweet_id, action))
+            .header("Authorization", format!("Bearer {}", alices_token))
+            .send(&server)
+            .await;
+        assert_eq!(status, 404);
+    }
+
+    let (json, status, _) = get("/users/bob/tweets")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_json_eq!(json, json!({ "data": [] }));
+}
+
+#[async_std::test]
+async fn cannot_block_self() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status) = block("bob", &bobs_token, &server).await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "cannot_block_self",
+            }
+        })
+    );
+
+    let (_, status) = block("nobody", &bobs_token, &server).await;
+    assert_eq!(status, 404);
+}
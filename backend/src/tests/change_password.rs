@@ -0,0 +1,108 @@
+use crate::tests::test_helpers::*;
+
+async fn login(password: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = post(
+        "/users/bob/session",
+        Some(LoginPayload {
+            password: password.to_string(),
+        }),
+    )
+    .send(server)
+    .await;
+    (json, status)
+}
+
+#[async_std::test]
+async fn changing_password() {
+    let server = test_setup().await;
+
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (_, status, _) = put(
+        "/me/password",
+        Some(ChangePasswordPayload {
+            current_password: "foobar".to_string(),
+            new_password: "hunter2".to_string(),
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(&server)
+    .await;
+    assert_eq!(status, 200);
+
+    let (_, status) = login("foobar", &server).await;
+    assert_eq!(status, 403);
+
+    let (_, status) = login("hunter2", &server).await;
+    assert_eq!(status, 201);
+}
+
+#[async_std::test]
+async fn changing_password_signs_out_other_sessions() {
+    let server = test_setup().await;
+
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let (json, _) = login("foobar", &server).await;
+    let other_token = json["data"]["token"].as_str().unwrap().to_string();
+
+    let (_, status, _) = put(
+        "/me/password",
+        Some(ChangePasswordPayload {
+            current_password: "foobar".to_string(),
+            new_password: "hunter2".to_string(),
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(&server)
+    .await;
+    assert_eq!(status, 200);
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", other_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 401);
+}
+
+#[async_std::test]
+async fn changing_password_requires_the_current_one() {
+    let server = test_setup().await;
+
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status, _) = put(
+        "/me/password",
+        Some(ChangePasswordPayload {
+            current_password: "wrong".to_string(),
+            new_password: "hunter2".to_string(),
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(&server)
+    .await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "incorrect_password",
+                "message": "Current password is incorrect"
+            }
+        })
+    );
+
+    let (_, status) = login("foobar", &server).await;
+    assert_eq!(status, 201);
+}
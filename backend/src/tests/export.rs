@@ -0,0 +1,79 @@
+use crate::tests::test_helpers::*;
+use shared::responses::ExportArchive;
+use std::io::{Cursor, Read};
+
+async fn set_up_bob_and_alice(server: &TestServer) -> String {
+    let bobs_token = token_for("bob", server).await;
+    let alices_token = token_for("alice", server).await;
+
+    for text in &["First", "Second"] {
+        tweet(text, &bobs_token, server).await;
+    }
+    follow("alice", &bobs_token, server).await;
+    follow("bob", &alices_token, server).await;
+
+    bobs_token
+}
+
+fn usernames(users: &[UserResponse]) -> Vec<&str> {
+    users.iter().map(|user| user.username.as_str()).collect()
+}
+
+#[async_std::test]
+async fn exporting_as_json() {
+    let server = test_setup().await;
+    let token = set_up_bob_and_alice(&server).await;
+
+    let (json, status, headers) = get("/me/export")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_eq!(
+        headers["content-disposition"],
+        "attachment; filename=\"witter-bob-export.json\""
+    );
+
+    let archive = serde_json::from_value::<ExportArchive>(json).unwrap();
+    assert_eq!(archive.user.username, "bob");
+    assert_eq!(
+        archive.tweets.iter().map(|tweet| tweet.text.as_str()).collect::<Vec<_>>(),
+        vec!["First", "Second"],
+    );
+    assert!(archive.tweets.iter().all(|tweet| tweet.user.username == "bob"));
+    assert_eq!(usernames(&archive.following), vec!["alice"]);
+    assert_eq!(usernames(&archive.followers), vec!["alice"]);
+}
+
+#[async_std::test]
+async fn exporting_as_zip() {
+    let server = test_setup().await;
+    let token = set_up_bob_and_alice(&server).await;
+
+    let (body, status, headers) = get("/me/export?format=zip")
+        .header("Authorization", format!("Bearer {}", token))
+        .send_for_bytes(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_eq!(headers["content-type"], "application/zip");
+
+    let mut zip = zip::ZipArchive::new(Cursor::new(body)).unwrap();
+    let mut json = String::new();
+    zip.by_name("export.json")
+        .unwrap()
+        .read_to_string(&mut json)
+        .unwrap();
+
+    let archive = serde_json::from_str::<ExportArchive>(&json).unwrap();
+    assert_eq!(archive.user.username, "bob");
+    assert_eq!(archive.tweets.len(), 2);
+}
+
+#[async_std::test]
+async fn exporting_requires_authentication() {
+    let server = test_setup().await;
+    set_up_bob_and_alice(&server).await;
+
+    let (_, status, _) = get("/me/export").send(&server).await;
+    assert_eq!(status, 400);
+}
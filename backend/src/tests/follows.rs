@@ -102,15 +102,81 @@ async fn follow_same_user_twice() {
         .header("Authorization", format!("Bearer {}", bobs_token))
         .send(&mut server)
         .await;
-    assert_eq!(status, 422);
+    assert_eq!(status, 200);
+    assert_json_eq!(json, json!({ "data": null }));
+
+    let (json, _, _) = get("/users/alice/followers").send(&mut server).await;
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+}
+
+#[async_std::test]
+async fn unfollowing() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    create_user_and_authenticate(&server, Some("alice".to_string())).await;
+
+    empty_post("/users/alice/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+
+    // The second time there's nothing left to undo, which is fine
+    for _ in 0..2 {
+        let (json, status, _) = delete("/users/alice/follow")
+            .header("Authorization", format!("Bearer {}", bobs_token))
+            .send(&server)
+            .await;
+        assert_eq!(status, 200);
+        assert_json_eq!(json, json!({ "data": null }));
+    }
+
+    let (json, _, _) = get("/users/bob/following").send(&server).await;
+    assert_json_eq!(json, json!({ "data": [] }));
+}
+
+#[async_std::test]
+async fn following_unknown_users() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status, _) = empty_post("/users/nobody/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
     assert_json_include!(
         actual: json,
         expected: json!({
             "error": {
-                "message": "You cannot follow the same user twice",
+                "code": "user_not_found",
             }
         })
     );
+
+    let (_, status, _) = delete("/users/nobody/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
+
+    for url in &["/users/nobody/followers", "/users/nobody/following"] {
+        let (json, status, _) = get(url).send(&server).await;
+        assert_eq!(status, 404);
+        assert_json_include!(
+            actual: json,
+            expected: json!({
+                "error": {
+                    "code": "user_not_found",
+                }
+            })
+        );
+    }
 }
 
 #[async_std::test]
@@ -130,6 +196,7 @@ async fn cannot_follow_self() {
         actual: json,
         expected: json!({
             "error": {
+                "code": "cannot_follow_self",
                 "message": "You cannot follow yourself",
             }
         })
@@ -0,0 +1,167 @@
+use crate::clock::*;
+use crate::tests::test_helpers::*;
+use chrono::Duration;
+
+async fn like(id: &str, token: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = empty_post(&format!("/tweets/{}/like", id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    (json, status)
+}
+
+#[async_std::test]
+async fn liking_and_unliking_a_tweet() {
+    let server = test_setup().await;
+    let alices_token = token_for("alice", &server).await;
+    let bobs_token = token_for("bob", &server).await;
+    let id = tweet("Like me", &alices_token, &server).await;
+
+    let (json, status) = like(&id, &bobs_token, &server).await;
+    assert_eq!(status, 201);
+    assert_json_eq!(json, json!({ "data": null }));
+    let (_, status) = like(&id, &bobs_token, &server).await;
+    assert_eq!(status, 200);
+
+    let (json, _, _) = get(&format!("/tweets/{}", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": { "like_count": 1, "liked_by_me": true } })
+    );
+
+    let (json, _, _) = get(&format!("/tweets/{}", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": { "like_count": 1, "liked_by_me": false } })
+    );
+
+    let (json, _, _) = get(&format!("/tweets/{}", id)).send(&server).await;
+    assert_json_include!(actual: json, expected: json!({ "data": { "like_count": 1 } }));
+    assert!(json["data"].get("liked_by_me").is_none());
+
+    let (_, status, _) = delete(&format!("/tweets/{}/like", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    let (json, _, _) = get(&format!("/tweets/{}", id)).send(&server).await;
+    assert_json_include!(actual: json, expected: json!({ "data": { "like_count": 0 } }));
+}
+
+#[async_std::test]
+async fn liking_unknown_and_protected_tweets() {
+    let server = test_setup().await;
+    let alices_token = token_for("alice", &server).await;
+    let bobs_token = token_for("bob", &server).await;
+
+    let (json, status) = like(&uuid::Uuid::new_v4().to_string(), &bobs_token, &server).await;
+    assert_eq!(status, 404);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "tweet_not_found" } })
+    );
+
+    patch(
+        "/me",
+        Some(UpdateProfilePayload {
+            protected: Some(true),
+            ..Default::default()
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", alices_token))
+    .send(&server)
+    .await;
+    let id = tweet("Just for friends", &alices_token, &server).await;
+
+    let (json, status) = like(&id, &bobs_token, &server).await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "protected_account" } })
+    );
+}
+
+#[async_std::test]
+async fn listing_a_users_likes() {
+    let server = test_setup().await;
+    let alices_token = token_for("alice", &server).await;
+    let bobs_token = token_for("bob", &server).await;
+    let first = tweet("First", &alices_token, &server).await;
+    let second = tweet("Second", &alices_token, &server).await;
+
+    freeze_time::<(), _, _>(start(), || async {
+        like(&second, &bobs_token, &server).await;
+    })
+    .await;
+    freeze_time::<(), _, _>(start() + Duration::minutes(1), || async {
+        like(&first, &bobs_token, &server).await;
+    })
+    .await;
+
+    let (json, status, _) = get("/users/bob/likes")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": [
+                {
+                    "id": first,
+                    "like_count": 1,
+                    "liked_by_me": false,
+                    "user": { "username": "alice" },
+                },
+                {
+                    "id": second,
+                    "like_count": 1,
+                    "liked_by_me": false,
+                    "user": { "username": "alice" },
+                },
+            ]
+        })
+    );
+    assert_eq!(json["data"].as_array().unwrap().len(), 2);
+
+    let (json, _, _) = get("/users/bob/likes?page_size=1&page=2").send(&server).await;
+    assert_json_include!(actual: json, expected: json!({ "data": [{ "id": second }] }));
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+
+    let (json, status, _) = get("/users/nobody/likes").send(&server).await;
+    assert_eq!(status, 404);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "user_not_found" } })
+    );
+}
+
+#[async_std::test]
+async fn timeline_shows_likes() {
+    let server = test_setup().await;
+    let token = token_for("bob", &server).await;
+    let liked = tweet("Liked", &token, &server).await;
+    tweet("Not liked", &token, &server).await;
+    like(&liked, &token, &server).await;
+
+    let (json, _, _) = get("/me/timeline")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": [
+                { "text": "Not liked", "like_count": 0, "liked_by_me": false },
+                { "text": "Liked", "like_count": 1, "liked_by_me": true },
+            ]
+        })
+    );
+}
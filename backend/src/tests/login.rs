@@ -16,6 +16,7 @@ async fn authenticating_without_auth_header() {
         actual: json,
         expected: json!({
             "error": {
+                "code": "missing_auth_token",
                 "message": "Missing value for `Authorization` header"
             }
         })
@@ -36,10 +37,10 @@ async fn authenticating_with_invalid_auth_header() {
 }
 
 #[async_std::test]
-async fn logging_in_with_unknown_user_gives_404() {
+async fn logging_in_with_unknown_user_looks_like_an_invalid_password() {
     let mut server = test_setup().await;
 
-    let (_, status, _) = post(
+    let (json, status, _) = post(
         "/users/bob/session",
         Some(LoginPayload {
             password: "foobar".to_string(),
@@ -47,7 +48,18 @@ async fn logging_in_with_unknown_user_gives_404() {
     )
     .send(&mut server)
     .await;
-    assert_eq!(status, 404);
+    assert_eq!(status, 403);
+
+    assert_json_eq!(
+        json,
+        json!({
+            "error": {
+                "status_code": "403",
+                "code": "invalid_credentials",
+                "message": "Invalid username or password",
+            }
+        }),
+    );
 }
 
 #[async_std::test]
@@ -67,12 +79,13 @@ async fn logging_in_with_invalid_password() {
     .await;
     assert_eq!(status, 403);
 
-    assert_json_include!(
-        actual: json,
-        expected: json!({
+    assert_json_eq!(
+        json,
+        json!({
             "error": {
                 "status_code": "403",
-                "message": "Something went wrong",
+                "code": "invalid_credentials",
+                "message": "Invalid username or password",
             }
         }),
     );
@@ -0,0 +1,142 @@
+use crate::clock::*;
+use crate::tests::test_helpers::*;
+use chrono::prelude::*;
+use chrono::Duration;
+
+async fn login_at(
+    time: DateTime<Utc>,
+    username: &str,
+    password: &str,
+    ip_address: &str,
+    server: &TestServer,
+) -> (StatusCode, Option<String>) {
+    freeze_time::<(StatusCode, Option<String>), _, _>(time, || async {
+        let (_, status, headers) = post(
+            &format!("/users/{}/session", username),
+            Some(LoginPayload {
+                password: password.to_string(),
+            }),
+        )
+        .header("X-Forwarded-For", ip_address)
+        .send(server)
+        .await;
+        (status, headers.get("retry-after").cloned())
+    })
+    .await
+}
+
+#[async_std::test]
+async fn backs_off_after_repeated_failures() {
+    let server = test_setup().await;
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+
+    for _ in 0..5 {
+        let (status, _) = login_at(start(), "bob", "wrong", "10.0.0.1", &server).await;
+        assert_eq!(status, 403);
+    }
+
+    let (status, retry_after) = login_at(start(), "bob", "wrong", "10.0.0.1", &server).await;
+    assert_eq!(status, 429);
+    assert_eq!(retry_after.as_deref(), Some("1"));
+
+    let later = start() + Duration::seconds(2);
+    let (status, _) = login_at(later, "bob", "wrong", "10.0.0.1", &server).await;
+    assert_eq!(status, 403);
+
+    let (status, retry_after) = login_at(later, "bob", "wrong", "10.0.0.1", &server).await;
+    assert_eq!(status, 429);
+    assert_eq!(retry_after.as_deref(), Some("2"));
+}
+
+#[async_std::test]
+async fn successful_login_resets_the_counters() {
+    let server = test_setup().await;
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+
+    for _ in 0..5 {
+        login_at(start(), "bob", "wrong", "10.0.0.1", &server).await;
+    }
+
+    let later = start() + Duration::seconds(2);
+    let (status, _) = login_at(later, "bob", "foobar", "10.0.0.1", &server).await;
+    assert_eq!(status, 201);
+
+    let (status, _) = login_at(later, "bob", "wrong", "10.0.0.1", &server).await;
+    assert_eq!(status, 403);
+}
+
+#[async_std::test]
+async fn account_is_locked_after_many_failures() {
+    let server = test_setup().await;
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+
+    // A minute apart is longer than any of the backoffs in between
+    for minute in 0..10 {
+        let time = start() + Duration::minutes(minute);
+        let (status, _) = login_at(time, "bob", "wrong", "10.0.0.1", &server).await;
+        assert_eq!(status, 403);
+    }
+
+    let time = start() + Duration::minutes(10);
+    let (status, retry_after) = login_at(time, "bob", "foobar", "10.0.0.2", &server).await;
+    assert_eq!(status, 429);
+    assert_eq!(retry_after.as_deref(), Some("840"));
+
+    let time = start() + Duration::minutes(25);
+    let (status, _) = login_at(time, "bob", "foobar", "10.0.0.2", &server).await;
+    assert_eq!(status, 201);
+}
+
+#[async_std::test]
+async fn backs_off_per_ip_address() {
+    let server = test_setup_with_config(|config| config.trust_proxy_headers = true).await;
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+
+    for i in 0..20 {
+        let username = format!("user{}", i);
+        let (status, _) = login_at(start(), &username, "wrong", "10.0.0.1", &server).await;
+        assert_ne!(status, 429);
+    }
+
+    let (status, _) = login_at(start(), "bob", "foobar", "10.0.0.1", &server).await;
+    assert_eq!(status, 429);
+
+    let (status, _) = login_at(start(), "bob", "foobar", "10.0.0.2", &server).await;
+    assert_eq!(status, 201);
+}
+
+#[async_std::test]
+async fn logging_in_does_not_reset_the_ip_backoff_for_other_usernames() {
+    let server = test_setup().await;
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+
+    for i in 0..20 {
+        let username = format!("user{}", i);
+        login_at(start(), &username, "wrong", "10.0.0.1", &server).await;
+    }
+
+    let later = start() + Duration::seconds(2);
+    let (status, _) = login_at(later, "bob", "foobar", "10.0.0.1", &server).await;
+    assert_eq!(status, 201);
+
+    let (status, _) = login_at(later, "user0", "wrong", "10.0.0.1", &server).await;
+    assert_eq!(status, 403);
+
+    let (status, retry_after) = login_at(later, "user1", "wrong", "10.0.0.1", &server).await;
+    assert_eq!(status, 429);
+    assert_eq!(retry_after.as_deref(), Some("2"));
+}
+
+#[async_std::test]
+async fn forwarded_for_is_ignored_unless_proxies_are_trusted() {
+    let server = test_setup().await;
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+
+    for i in 0..20 {
+        let username = format!("user{}", i);
+        login_at(start(), &username, "wrong", "10.0.0.1", &server).await;
+    }
+
+    let (status, _) = login_at(start(), "bob", "foobar", "10.0.0.2", &server).await;
+    assert_eq!(status, 429);
+}
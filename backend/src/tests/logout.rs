@@ -25,4 +25,27 @@ async fn logging_out() {
     .send(&mut server)
     .await;
     assert_eq!(status, 401);
-}
\ No newline at end of file
+}
+
+#[async_std::test]
+async fn logging_out_someone_else_is_rejected() {
+    let server = test_setup().await;
+    let bobs_token = token_for("bob", &server).await;
+    token_for("alice", &server).await;
+
+    let (json, status, _) = delete("/users/alice/session")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "forbidden" } })
+    );
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+}
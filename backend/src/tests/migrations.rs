@@ -0,0 +1,116 @@
+use crate::migrations::{self, MIGRATIONS};
+use crate::tests::test_helpers::*;
+use sqlx::prelude::*;
+
+#[async_std::test]
+async fn test_db_is_fully_migrated() {
+    let server = test_setup().await;
+
+    let mut applied = migrations::applied_versions(&server.db_pool()).await.unwrap();
+    applied.sort();
+
+    let expected = MIGRATIONS.iter().map(|m| m.version).collect::<Vec<_>>();
+    assert_eq!(applied, expected);
+}
+
+#[async_std::test]
+async fn migrating_twice_is_a_no_op() {
+    let server = test_setup().await;
+
+    let ran = migrations::migrate(&server.db_pool()).await.unwrap();
+    assert!(ran.is_empty());
+}
+
+#[async_std::test]
+async fn rolling_back_and_reapplying_everything() {
+    let server = test_setup().await;
+    let db_pool = server.db_pool();
+
+    let reverted = migrations::rollback(&db_pool, MIGRATIONS.len()).await.unwrap();
+    assert_eq!(reverted.len(), MIGRATIONS.len());
+    assert!(migrations::applied_versions(&db_pool).await.unwrap().is_empty());
+
+    let ran = migrations::migrate(&db_pool).await.unwrap();
+    assert_eq!(ran.len(), MIGRATIONS.len());
+
+    create_user_and_authenticate(&server, None).await;
+}
+
+#[async_std::test]
+async fn existing_auth_tokens_are_hashed_in_place() {
+    let server = test_setup().await;
+    let db_pool = server.db_pool();
+
+    migrations::rollback(&db_pool, MIGRATIONS.len() - 2).await.unwrap();
+
+    let user_id = uuid::Uuid::new_v4();
+    sqlx::query(
+        r#"
+            insert into users (id, username, hashed_password, created_at, updated_at)
+            values ($1, 'bob', 'not a real hash', now(), now())
+        "#,
+    )
+    .bind(user_id)
+    .execute(&db_pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        r#"
+            insert into auth_tokens (
+                id, user_id, token, expires_at, last_used_at, created_at, updated_at
+            )
+            values ($1, $2, 'legacy-token', now() + interval '1 day', now(), now(), now())
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4())
+    .bind(user_id)
+    .execute(&db_pool)
+    .await
+    .unwrap();
+
+    migrations::migrate(&db_pool).await.unwrap();
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", "Bearer legacy-token")
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn usernames_differing_only_by_case_are_renamed() {
+    let server = test_setup().await;
+    let db_pool = server.db_pool();
+
+    migrations::rollback(&db_pool, MIGRATIONS.len() - 5).await.unwrap();
+
+    let older_id = uuid::Uuid::new_v4();
+    let newer_id = uuid::Uuid::new_v4();
+    sqlx::query(
+        r#"
+            insert into users (id, username, hashed_password, created_at, updated_at)
+            values
+                ($1, 'bob', 'not a real hash', now() - interval '1 day', now()),
+                ($2, 'Bob', 'not a real hash', now(), now())
+        "#,
+    )
+    .bind(older_id)
+    .bind(newer_id)
+    .execute(&db_pool)
+    .await
+    .unwrap();
+
+    migrations::migrate(&db_pool).await.unwrap();
+
+    let usernames = sqlx::query("select username from users order by created_at")
+        .fetch_all(&db_pool)
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get::<String, _>("username"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        usernames,
+        vec!["bob".to_string(), format!("Bob_{}", &newer_id.to_string()[..8])]
+    );
+}
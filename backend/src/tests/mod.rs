@@ -8,3 +8,24 @@ mod posting_tweets;
 mod follows;
 mod timeline;
 mod users;
+mod migrations;
+mod sessions;
+mod change_password;
+mod password_resets;
+mod login_throttling;
+mod username_validation;
+mod account_deletion;
+mod export;
+mod profiles;
+mod uploads;
+mod protected_accounts;
+mod blocks;
+mod mutes;
+mod user_counts;
+mod routes;
+mod panics;
+mod tweet_permalinks;
+mod tweet_editing;
+mod replies;
+mod retweets;
+mod likes;
@@ -0,0 +1,197 @@
+use crate::tests::test_helpers::*;
+
+/// Bob follows alice, who has posted once.
+async fn setup_follow(server: &TestServer) -> (String, String) {
+    let bobs_token = token_for("bob", server).await;
+    let alices_token = token_for("alice", server).await;
+    follow("alice", &bobs_token, server).await;
+    tweet("Hello from alice", &alices_token, server).await;
+    (bobs_token, alices_token)
+}
+
+#[async_std::test]
+async fn muting_a_user() {
+    let server = test_setup().await;
+    let (bobs_token, _) = setup_follow(&server).await;
+    assert_eq!(timeline_texts(&bobs_token, &server).await, vec!["Hello from alice"]);
+
+    let (json, status, _) = post("/users/alice/mute", Some(MuteUserPayload::default()))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 201);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "user": { "username": "alice" },
+                "expires_at": null,
+            }
+        })
+    );
+
+    let (_, status, _) = post("/users/alice/mute", Some(MuteUserPayload::default()))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    assert!(timeline_texts(&bobs_token, &server).await.is_empty());
+
+    // Still following, just not seeing anything
+    let (json, _, _) = get("/users/bob/following").send(&server).await;
+    assert_json_include!(actual: json, expected: json!({ "data": [{ "username": "alice" }] }));
+
+    let (json, _, _) = get("/me/mutes/users")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": [{ "user": { "username": "alice" } }] })
+    );
+
+    let (_, status, _) = delete("/users/alice/mute")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    assert_eq!(timeline_texts(&bobs_token, &server).await, vec!["Hello from alice"]);
+}
+
+#[async_std::test]
+async fn mutes_expire() {
+    use crate::clock::*;
+    use chrono::prelude::*;
+
+    let server = test_setup().await;
+    let (bobs_token, _) = setup_follow(&server).await;
+
+    let expires_at = Utc::now() + chrono::Duration::hours(1);
+    post(
+        "/users/alice/mute",
+        Some(MuteUserPayload {
+            expires_at: Some(expires_at),
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", bobs_token))
+    .send(&server)
+    .await;
+    assert!(timeline_texts(&bobs_token, &server).await.is_empty());
+
+    let later = expires_at + chrono::Duration::minutes(1);
+    let texts = freeze_time::<Vec<String>, _, _>(later, || async {
+        timeline_texts(&bobs_token, &server).await
+    })
+    .await;
+    assert_eq!(texts, vec!["Hello from alice"]);
+
+    let (json, status, _) = post(
+        "/users/alice/mute",
+        Some(MuteUserPayload {
+            expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", bobs_token))
+    .send(&server)
+    .await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "errors": { "expires_at": ["must be in the future"] }
+            }
+        })
+    );
+}
+
+#[async_std::test]
+async fn muting_keywords() {
+    let server = test_setup().await;
+    let (bobs_token, alices_token) = setup_follow(&server).await;
+    tweet("Spoilers for the finale", &alices_token, &server).await;
+    tweet("Bob finished the finale", &bobs_token, &server).await;
+
+    let (json, status, _) = post(
+        "/me/mutes/keywords",
+        Some(MuteKeywordPayload {
+            keyword: "  FINALE ".to_string(),
+            expires_at: None,
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", bobs_token))
+    .send(&server)
+    .await;
+    assert_eq!(status, 201);
+    assert_json_include!(actual: json, expected: json!({ "data": { "keyword": "FINALE" } }));
+    let id = json["data"]["id"].as_str().unwrap().to_string();
+
+    // Your own tweets are never hidden from you
+    assert_eq!(
+        timeline_texts(&bobs_token, &server).await,
+        vec!["Bob finished the finale", "Hello from alice"]
+    );
+
+    let (json, _, _) = get("/me/mutes/keywords")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+
+    let (_, status, _) = delete(&format!("/me/mutes/keywords/{}", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_eq!(timeline_texts(&bobs_token, &server).await.len(), 3);
+
+    let (json, status, _) = delete(&format!("/me/mutes/keywords/{}", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "mute_not_found" } })
+    );
+}
+
+#[async_std::test]
+async fn invalid_mutes() {
+    let server = test_setup().await;
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status, _) = post("/users/bob/mute", Some(MuteUserPayload::default()))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "cannot_mute_self" } })
+    );
+
+    let (json, status, _) = post(
+        "/me/mutes/keywords",
+        Some(MuteKeywordPayload {
+            keyword: " ".to_string(),
+            expires_at: None,
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", bobs_token))
+    .send(&server)
+    .await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "errors": { "keyword": ["can't be blank"] }
+            }
+        })
+    );
+}
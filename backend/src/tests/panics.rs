@@ -0,0 +1,42 @@
+use crate::middlewares::{CatchPanics, ErrorReponseToJson};
+use crate::tests::test_helpers::*;
+
+async fn blow_up(_: tide::Request<()>) -> tide::Result {
+    panic!("handler blew up")
+}
+
+async fn send(app: &tide::Server<()>, path: &str) -> (Value, StatusCode) {
+    let url = Url::parse(&format!("http://example.com{}", path)).unwrap();
+    let mut res: Response = app.respond(Request::new(Method::Get, url)).await.unwrap();
+    let body = res.body_bytes().await.unwrap();
+    (serde_json::from_slice(&body).unwrap(), res.status())
+}
+
+// No real endpoint panics on purpose, so this builds a server of its own with
+// the middleware in the same order as `server`.
+#[async_std::test]
+async fn panics_become_json_errors() {
+    let mut app = tide::new();
+    app.with(ErrorReponseToJson);
+    app.with(CatchPanics);
+    app.at("/panic").get(blow_up);
+    app.at("/fine")
+        .get(|_: tide::Request<()>| async { Ok(json!({ "data": null })) });
+
+    let (json, status) = send(&app, "/panic").await;
+    assert_eq!(status, 500);
+    assert_json_eq!(
+        json,
+        json!({
+            "error": {
+                "status_code": "500",
+                "code": "internal_error",
+                "message": "Something went wrong",
+            }
+        })
+    );
+
+    // The server is still fine afterwards
+    let (_, status) = send(&app, "/fine").await;
+    assert_eq!(status, 200);
+}
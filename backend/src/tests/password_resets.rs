@@ -0,0 +1,169 @@
+use crate::tests::test_helpers::*;
+
+async fn create_user_with_email(server: &TestServer) -> String {
+    let (json, status, _) = post(
+        "/users",
+        Some(CreateUserPayload {
+            username: "bob".to_string(),
+            password: "foobar".to_string(),
+            email: Some("bob@example.com".to_string()),
+        }),
+    )
+    .send(server)
+    .await;
+    assert_eq!(status, 201);
+
+    json["data"]["token"].as_str().unwrap().to_string()
+}
+
+async fn request_reset(email: &str, server: &TestServer) -> StatusCode {
+    let (_, status, _) = post(
+        "/password_resets",
+        Some(RequestPasswordResetPayload {
+            email: email.to_string(),
+        }),
+    )
+    .send(server)
+    .await;
+    status
+}
+
+async fn confirm_reset(token: &str, new_password: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = post(
+        "/password_resets/confirm",
+        Some(ConfirmPasswordResetPayload {
+            token: token.to_string(),
+            new_password: new_password.to_string(),
+        }),
+    )
+    .send(server)
+    .await;
+    (json, status)
+}
+
+fn token_from_email(email: &Email) -> String {
+    let start = email.body.find("token=").unwrap() + "token=".len();
+    email.body[start..]
+        .chars()
+        .take_while(|c| c.is_alphanumeric())
+        .collect()
+}
+
+async fn login(password: &str, server: &TestServer) -> StatusCode {
+    let (_, status, _) = post(
+        "/users/bob/session",
+        Some(LoginPayload {
+            password: password.to_string(),
+        }),
+    )
+    .send(server)
+    .await;
+    status
+}
+
+#[async_std::test]
+async fn resetting_a_forgotten_password() {
+    let server = test_setup().await;
+
+    let old_token = create_user_with_email(&server).await;
+
+    assert_eq!(request_reset("BOB@example.com", &server).await, 202);
+
+    let emails = server.wait_for_emails(1).await;
+    assert_eq!(emails.len(), 1);
+    assert_eq!(emails[0].to, "bob@example.com");
+
+    let token = token_from_email(&emails[0]);
+    let (_, status) = confirm_reset(&token, "hunter2", &server).await;
+    assert_eq!(status, 200);
+
+    assert_eq!(login("foobar", &server).await, 403);
+    assert_eq!(login("hunter2", &server).await, 201);
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", old_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 401);
+}
+
+#[async_std::test]
+async fn unknown_email_looks_the_same_but_sends_nothing() {
+    let server = test_setup().await;
+
+    create_user_with_email(&server).await;
+
+    assert_eq!(request_reset("alice@example.com", &server).await, 202);
+    async_std::task::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(server.sent_emails().await.is_empty());
+}
+
+#[async_std::test]
+async fn reset_tokens_are_single_use() {
+    let server = test_setup().await;
+
+    create_user_with_email(&server).await;
+    request_reset("bob@example.com", &server).await;
+    let token = token_from_email(&server.wait_for_emails(1).await[0]);
+
+    let (_, status) = confirm_reset(&token, "hunter2", &server).await;
+    assert_eq!(status, 200);
+
+    let (json, status) = confirm_reset(&token, "letmein", &server).await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "invalid_reset_token",
+                "message": "Password reset token is invalid or has expired"
+            }
+        })
+    );
+    assert_eq!(login("hunter2", &server).await, 201);
+}
+
+#[async_std::test]
+async fn reset_tokens_cannot_be_used_twice_at_once() {
+    let server = test_setup().await;
+
+    create_user_with_email(&server).await;
+    request_reset("bob@example.com", &server).await;
+    let token = token_from_email(&server.wait_for_emails(1).await[0]);
+
+    let ((_, first), (_, second)) = futures::join!(
+        confirm_reset(&token, "hunter2", &server),
+        confirm_reset(&token, "letmein", &server),
+    );
+    let statuses = vec![first, second];
+    assert!(statuses.contains(&StatusCode::Ok));
+    assert!(statuses.contains(&StatusCode::UnprocessableEntity));
+
+    let logins = vec![
+        login("hunter2", &server).await,
+        login("letmein", &server).await,
+    ];
+    assert!(logins.contains(&StatusCode::Created));
+    assert!(logins.contains(&StatusCode::Forbidden));
+}
+
+#[async_std::test]
+async fn reset_tokens_expire() {
+    use crate::clock::*;
+    use chrono::prelude::*;
+
+    let server = test_setup().await;
+
+    create_user_with_email(&server).await;
+
+    let time = Utc.ymd(1970, 1, 1).and_hms(0, 0, 0);
+    freeze_time::<(), _, _>(time, || async {
+        request_reset("bob@example.com", &server).await;
+    })
+    .await;
+    let token = token_from_email(&server.wait_for_emails(1).await[0]);
+
+    let (_, status) = confirm_reset(&token, "hunter2", &server).await;
+    assert_eq!(status, 422);
+    assert_eq!(login("foobar", &server).await, 201);
+}
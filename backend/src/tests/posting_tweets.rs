@@ -10,6 +10,7 @@ async fn posting_a_valid_tweet() {
         "/tweets",
         Some(CreateTweetPayload {
             text: "Hello, World!".to_string(),
+            ..Default::default()
         },
     ))
     .header("Authorization", format!("Bearer {}", token))
@@ -36,16 +37,23 @@ async fn posting_a_tweet_that_is_too_long() {
     let token = create_user_and_authenticate(&mut server, None).await.token;
 
     let text = std::iter::repeat('a').take(1000).collect::<String>();
-    let (json, status, _) = post("/tweets", Some(CreateTweetPayload { text }))
-        .header("Authorization", format!("Bearer {}", token))
-        .send(&mut server)
-        .await;
+    let (json, status, _) = post(
+        "/tweets",
+        Some(CreateTweetPayload {
+            text,
+            ..Default::default()
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(&mut server)
+    .await;
     assert_eq!(status, 422);
 
     assert_json_include!(
         actual: json,
         expected: json!({
             "error": {
+                "code": "tweet_too_long",
                 "message": format!("Tweet is too long. Max then is {}", MAX_TWEET_LENGTH)
             }
         })
@@ -61,10 +69,16 @@ async fn posting_a_tweet_that_is_exactly_the_max_length() {
     let token = create_user_and_authenticate(&mut server, None).await.token;
 
     let text = std::iter::repeat('a').take(MAX_TWEET_LENGTH).collect::<String>();
-    let (json, status, _) = post("/tweets", Some(CreateTweetPayload { text }))
-        .header("Authorization", format!("Bearer {}", token))
-        .send(&mut server)
-        .await;
+    let (json, status, _) = post(
+        "/tweets",
+        Some(CreateTweetPayload {
+            text,
+            ..Default::default()
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(&mut server)
+    .await;
     assert_eq!(status, 201);
 
     assert_json_include!(
@@ -92,6 +106,7 @@ async fn invalid_data_gets_mapped_to_a_422() {
         expected: json!({
             "error": {
                 "status_code": "422",
+                "code": "invalid_payload",
                 "message": "missing field `text` at line 1 column 14"
             }
         })
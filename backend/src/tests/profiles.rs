@@ -0,0 +1,132 @@
+use crate::tests::test_helpers::*;
+
+async fn update_profile(
+    payload: UpdateProfilePayload,
+    token: &str,
+    server: &TestServer,
+) -> (Value, StatusCode) {
+    let (json, status, _) = patch("/me", Some(payload))
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    (json, status)
+}
+
+#[async_std::test]
+async fn updating_profile() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status) = update_profile(
+        UpdateProfilePayload {
+            display_name: Some("Bob Bobson".to_string()),
+            bio: Some("Just here for the tweets".to_string()),
+            location: Some("Copenhagen".to_string()),
+            website: Some("https://bob.example.com".to_string()),
+            avatar_url: Some("https://bob.example.com/me.png".to_string()),
+            protected: None,
+        },
+        &token,
+        &server,
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "username": "bob",
+                "display_name": "Bob Bobson",
+            }
+        })
+    );
+
+    let (json, status, _) = get("/users/bob").send(&server).await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "display_name": "Bob Bobson",
+                "bio": "Just here for the tweets",
+                "location": "Copenhagen",
+                "website": "https://bob.example.com",
+                "avatar_url": "https://bob.example.com/me.png",
+            }
+        })
+    );
+}
+
+#[async_std::test]
+async fn fields_left_out_are_kept_and_blank_ones_cleared() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    update_profile(
+        UpdateProfilePayload {
+            display_name: Some("Bob".to_string()),
+            location: Some("Copenhagen".to_string()),
+            ..Default::default()
+        },
+        &token,
+        &server,
+    )
+    .await;
+
+    let (_, status) = update_profile(
+        UpdateProfilePayload {
+            location: Some("".to_string()),
+            ..Default::default()
+        },
+        &token,
+        &server,
+    )
+    .await;
+    assert_eq!(status, 200);
+
+    let (json, _, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    assert_eq!(json["data"]["display_name"], "Bob");
+    assert_eq!(json["data"]["location"], Value::Null);
+}
+
+#[async_std::test]
+async fn invalid_profiles_are_rejected() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status) = update_profile(
+        UpdateProfilePayload {
+            bio: Some("a".repeat(161)),
+            website: Some("javascript:alert(1)".to_string()),
+            ..Default::default()
+        },
+        &token,
+        &server,
+    )
+    .await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "validation_failed",
+                "errors": {
+                    "bio": ["is too long (maximum is 160 characters)"],
+                    "website": ["must be an http or https URL"],
+                }
+            }
+        })
+    );
+
+    let (json, _, _) = get("/users/bob").send(&server).await;
+    assert_eq!(json["data"]["bio"], Value::Null);
+}
@@ -0,0 +1,294 @@
+use crate::tests::test_helpers::*;
+
+async fn protect(token: &str, protected: bool, server: &TestServer) {
+    let (_, status, _) = patch(
+        "/me",
+        Some(UpdateProfilePayload {
+            protected: Some(protected),
+            ..Default::default()
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(server)
+    .await;
+    assert_eq!(status, 200);
+}
+
+async fn pending_request_ids(token: &str, server: &TestServer) -> Vec<String> {
+    let (json, status, _) = get("/me/follow_requests")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    assert_eq!(status, 200);
+    json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|request| request["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[async_std::test]
+async fn following_a_protected_account_sends_a_request() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    protect(&alices_token, true, &server).await;
+
+    // Asking twice leaves a single request
+    for _ in 0..2 {
+        let (json, status, _) = empty_post("/users/alice/follow")
+            .header("Authorization", format!("Bearer {}", bobs_token))
+            .send(&server)
+            .await;
+        assert_eq!(status, 202);
+        assert_json_eq!(json, json!({ "data": null }));
+    }
+
+    let (json, _, _) = get("/users/alice/followers").send(&server).await;
+    assert_json_eq!(json, json!({ "data": [] }));
+
+    let (json, status, _) = get("/me/follow_requests")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": [
+                { "user": { "username": "bob" } }
+            ]
+        })
+    );
+
+    let (json, _, _) = get("/users/alice").send(&server).await;
+    assert_json_include!(actual: json, expected: json!({ "data": { "protected": true } }));
+}
+
+#[async_std::test]
+async fn approving_a_follow_request() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    protect(&alices_token, true, &server).await;
+
+    empty_post("/users/alice/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    let id = pending_request_ids(&alices_token, &server).await.remove(0);
+
+    // Only the account that was asked can answer
+    let (_, status, _) = empty_post(&format!("/me/follow_requests/{}/approve", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
+
+    let (json, status, _) = empty_post(&format!("/me/follow_requests/{}/approve", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_json_eq!(json, json!({ "data": null }));
+
+    let (json, _, _) = get("/users/alice/followers").send(&server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": [{ "username": "bob" }] })
+    );
+    assert!(pending_request_ids(&alices_token, &server).await.is_empty());
+
+    let (json, status, _) = empty_post(&format!("/me/follow_requests/{}/approve", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "follow_request_not_found",
+            }
+        })
+    );
+}
+
+#[async_std::test]
+async fn rejecting_a_follow_request() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    protect(&alices_token, true, &server).await;
+
+    empty_post("/users/alice/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    let id = pending_request_ids(&alices_token, &server).await.remove(0);
+
+    let (_, status, _) = empty_post(&format!("/me/follow_requests/{}/reject", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    let (json, _, _) = get("/users/alice/followers").send(&server).await;
+    assert_json_eq!(json, json!({ "data": [] }));
+    assert!(pending_request_ids(&alices_token, &server).await.is_empty());
+
+    let (_, status, _) = empty_post(&format!("/me/follow_requests/{}/reject", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
+
+    let (_, status, _) = empty_post("/me/follow_requests/not-an-id/reject")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
+}
+
+#[async_std::test]
+async fn unfollowing_withdraws_a_request() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    protect(&alices_token, true, &server).await;
+
+    empty_post("/users/alice/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    delete("/users/alice/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+
+    assert!(pending_request_ids(&alices_token, &server).await.is_empty());
+}
+
+#[async_std::test]
+async fn protected_tweets_are_only_shown_to_followers() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    protect(&alices_token, true, &server).await;
+    tweet("just for friends", &alices_token, &server).await;
+
+    let (json, status, _) = get("/users/alice/tweets").send(&server).await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "protected_account",
+            }
+        })
+    );
+
+    let (_, status, _) = get("/users/alice/tweets")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 403);
+
+    // A pending request doesn't put anything on the timeline either
+    empty_post("/users/alice/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    let (json, _, _) = get("/me/timeline")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_json_eq!(json, json!({ "data": [] }));
+
+    let (json, status, _) = get("/users/alice/tweets")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": [{ "text": "just for friends" }] })
+    );
+
+    let id = pending_request_ids(&alices_token, &server).await.remove(0);
+    empty_post(&format!("/me/follow_requests/{}/approve", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+
+    for url in &["/users/alice/tweets", "/me/timeline"] {
+        let (json, status, _) = get(url)
+            .header("Authorization", format!("Bearer {}", bobs_token))
+            .send(&server)
+            .await;
+        assert_eq!(status, 200);
+        assert_json_include!(
+            actual: json,
+            expected: json!({
+                "data": [{ "text": "just for friends", "user": { "username": "alice" } }]
+            })
+        );
+    }
+}
+
+#[async_std::test]
+async fn unprotecting_approves_pending_requests() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    protect(&alices_token, true, &server).await;
+
+    empty_post("/users/alice/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    protect(&alices_token, false, &server).await;
+
+    assert!(pending_request_ids(&alices_token, &server).await.is_empty());
+    let (json, _, _) = get("/users/alice/followers").send(&server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": [{ "username": "bob" }] })
+    );
+
+    let (json, status, _) = get("/users/alice/tweets").send(&server).await;
+    assert_eq!(status, 200);
+    assert_json_eq!(json, json!({ "data": [] }));
+}
@@ -0,0 +1,186 @@
+use crate::tests::test_helpers::*;
+
+async fn reply(text: &str, in_reply_to_id: &str, token: &str, server: &TestServer) -> String {
+    let (json, status) = post_tweet(
+        CreateTweetPayload {
+            text: text.to_string(),
+            in_reply_to_id: Some(in_reply_to_id.parse().unwrap()),
+            ..Default::default()
+        },
+        token,
+        server,
+    )
+    .await;
+    assert_eq!(status, 201);
+    json["data"]["id"].as_str().unwrap().to_string()
+}
+
+#[async_std::test]
+async fn replying_to_a_tweet() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let root = tweet("First", &token, &server).await;
+    let first = reply("Second", &root, &token, &server).await;
+    let nested = reply("Third", &first, &token, &server).await;
+
+    let (json, _, _) = get(&format!("/tweets/{}", root)).send(&server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": { "in_reply_to_id": null, "conversation_id": root, "reply_count": 1 }
+        })
+    );
+
+    let (json, _, _) = get(&format!("/tweets/{}", nested)).send(&server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": { "in_reply_to_id": first, "conversation_id": root, "reply_count": 0 }
+        })
+    );
+}
+
+#[async_std::test]
+async fn replying_to_an_unknown_tweet() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, None).await.token;
+
+    let (json, status) = post_tweet(
+        CreateTweetPayload {
+            text: "Hi".to_string(),
+            in_reply_to_id: Some(uuid::Uuid::new_v4()),
+            ..Default::default()
+        },
+        &token,
+        &server,
+    )
+    .await;
+    assert_eq!(status, 404);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "tweet_not_found" } })
+    );
+}
+
+#[async_std::test]
+async fn conversations_have_ancestors_and_replies() {
+    let server = test_setup().await;
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+
+    let root = tweet("Root", &bobs_token, &server).await;
+    let first = reply("First reply", &root, &alices_token, &server).await;
+    let nested = reply("Nested reply", &first, &bobs_token, &server).await;
+    let second = reply("Second reply", &root, &alices_token, &server).await;
+
+    let (json, status, _) = get(&format!("/tweets/{}/conversation", first))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_eq!(json["data"]["ancestors"].as_array().unwrap().len(), 1);
+    assert_eq!(json["data"]["replies"].as_array().unwrap().len(), 1);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "ancestors": [{ "id": root }],
+                "tweet": { "id": first },
+                "replies": [{ "id": nested, "in_reply_to_id": first }],
+            }
+        })
+    );
+
+    let (json, _, _) = get(&format!("/tweets/{}/conversation?page_size=2", root))
+        .send(&server)
+        .await;
+    assert_eq!(json["data"]["replies"].as_array().unwrap().len(), 2);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "ancestors": [],
+                "tweet": { "id": root, "reply_count": 2 },
+                "replies": [{ "id": first }, { "id": nested }],
+            }
+        })
+    );
+
+    let (json, _, _) = get(&format!("/tweets/{}/conversation?page_size=2&page=2", root))
+        .send(&server)
+        .await;
+    assert_eq!(json["data"]["replies"].as_array().unwrap().len(), 1);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": { "replies": [{ "id": second }] } })
+    );
+}
+
+#[async_std::test]
+async fn conversations_leave_out_blocked_users() {
+    let server = test_setup().await;
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+
+    let root = tweet("Root", &bobs_token, &server).await;
+    reply("Reply", &root, &alices_token, &server).await;
+
+    empty_post("/users/alice/block")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+
+    let (json, _, _) = get(&format!("/tweets/{}/conversation", root))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert!(json["data"]["replies"].as_array().unwrap().is_empty());
+}
+
+#[async_std::test]
+async fn timeline_shows_replies_between_followed_users() {
+    let server = test_setup().await;
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    let carols_token = create_user_and_authenticate(&server, Some("carol".to_string()))
+        .await
+        .token;
+    follow("bob", &carols_token, &server).await;
+
+    let alices_tweet = tweet("Hi all", &alices_token, &server).await;
+    reply("Hi alice", &alices_tweet, &bobs_token, &server).await;
+    let bobs_tweet = tweet("A thread", &bobs_token, &server).await;
+    reply("Continued", &bobs_tweet, &bobs_token, &server).await;
+
+    assert_eq!(
+        timeline_texts(&carols_token, &server).await,
+        vec!["Continued", "A thread"]
+    );
+
+    follow("alice", &carols_token, &server).await;
+    assert_eq!(
+        timeline_texts(&carols_token, &server).await,
+        vec!["Continued", "A thread", "Hi alice", "Hi all"]
+    );
+
+    // Replies to the viewer always show up
+    let carols_tweet = tweet("Anyone?", &carols_token, &server).await;
+    reply("Me!", &carols_tweet, &bobs_token, &server).await;
+    assert_eq!(
+        timeline_texts(&carols_token, &server).await,
+        vec!["Me!", "Anyone?", "Continued", "A thread", "Hi alice", "Hi all"]
+    );
+}
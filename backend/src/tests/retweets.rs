@@ -0,0 +1,218 @@
+use crate::tests::test_helpers::*;
+use sqlx::prelude::*;
+
+async fn retweet(id: &str, token: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = empty_post(&format!("/tweets/{}/retweet", id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    (json, status)
+}
+
+async fn timeline(token: &str, server: &TestServer) -> Value {
+    let (json, status, _) = get("/me/timeline")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    assert_eq!(status, 200);
+    json
+}
+
+#[async_std::test]
+async fn retweets_show_up_for_followers_of_the_retweeter() {
+    let server = test_setup().await;
+    let alices_token = token_for("alice", &server).await;
+    let bobs_token = token_for("bob", &server).await;
+    let carols_token = token_for("carol", &server).await;
+    follow("bob", &carols_token, &server).await;
+
+    let id = tweet("Worth sharing", &alices_token, &server).await;
+
+    let (json, status) = retweet(&id, &bobs_token, &server).await;
+    assert_eq!(status, 201);
+    assert_json_eq!(json, json!({ "data": null }));
+    let (_, status) = retweet(&id, &bobs_token, &server).await;
+    assert_eq!(status, 200);
+
+    let json = timeline(&carols_token, &server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": [{
+                "id": id,
+                "text": "Worth sharing",
+                "retweet_count": 1,
+                "user": { "username": "alice" },
+                "retweeted_by": { "username": "bob" },
+            }]
+        })
+    );
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+
+    let (_, status, _) = delete(&format!("/tweets/{}/retweet", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    let json = timeline(&carols_token, &server).await;
+    assert_json_eq!(json, json!({ "data": [] }));
+}
+
+#[async_std::test]
+async fn retweets_of_tweets_already_in_the_timeline_are_left_out() {
+    let server = test_setup().await;
+    let alices_token = token_for("alice", &server).await;
+    let bobs_token = token_for("bob", &server).await;
+    let carols_token = token_for("carol", &server).await;
+    let daves_token = token_for("dave", &server).await;
+    let eves_token = token_for("eve", &server).await;
+    follow("alice", &carols_token, &server).await;
+    follow("bob", &carols_token, &server).await;
+    follow("dave", &carols_token, &server).await;
+
+    let alices_tweet = tweet("From alice", &alices_token, &server).await;
+    let eves_tweet = tweet("From eve", &eves_token, &server).await;
+    retweet(&alices_tweet, &bobs_token, &server).await;
+    retweet(&eves_tweet, &bobs_token, &server).await;
+    retweet(&eves_tweet, &daves_token, &server).await;
+
+    let json = timeline(&carols_token, &server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": [
+                { "id": eves_tweet, "retweet_count": 2, "retweeted_by": { "username": "dave" } },
+                { "id": alices_tweet, "retweet_count": 1, "retweeted_by": null },
+            ]
+        })
+    );
+    assert_eq!(json["data"].as_array().unwrap().len(), 2);
+}
+
+#[async_std::test]
+async fn deleting_a_tweet_deletes_its_retweets() {
+    let server = test_setup().await;
+    let alices_token = token_for("alice", &server).await;
+    let bobs_token = token_for("bob", &server).await;
+    let carols_token = token_for("carol", &server).await;
+    follow("bob", &carols_token, &server).await;
+
+    let id = tweet("Short lived", &alices_token, &server).await;
+    retweet(&id, &bobs_token, &server).await;
+
+    delete(&format!("/tweets/{}", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+
+    let json = timeline(&carols_token, &server).await;
+    assert_json_eq!(json, json!({ "data": [] }));
+    let row = sqlx::query("select count(*) from retweets")
+        .fetch_one(&server.db_pool())
+        .await
+        .unwrap();
+    assert_eq!(row.get::<i64, _>(0), 0);
+}
+
+#[async_std::test]
+async fn quote_tweets_embed_the_original() {
+    let server = test_setup().await;
+    let alices_token = token_for("alice", &server).await;
+    let bobs_token = token_for("bob", &server).await;
+
+    let original = tweet("Hot take", &alices_token, &server).await;
+    let (json, status) = post_tweet(
+        CreateTweetPayload {
+            text: "Disagree".to_string(),
+            quoted_tweet_id: Some(original.parse().unwrap()),
+            ..Default::default()
+        },
+        &bobs_token,
+        &server,
+    )
+    .await;
+    assert_eq!(status, 201);
+    let quote = json["data"]["id"].as_str().unwrap().to_string();
+
+    let (json, _, _) = get(&format!("/tweets/{}", quote)).send(&server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "text": "Disagree",
+                "quoted_tweet_id": original,
+                "quoted_tweet": {
+                    "id": original,
+                    "text": "Hot take",
+                    "user": { "username": "alice" },
+                },
+            }
+        })
+    );
+
+    delete(&format!("/tweets/{}", original))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+
+    let (json, status, _) = get(&format!("/tweets/{}", quote)).send(&server).await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": { "text": "Disagree", "quoted_tweet_id": null, "quoted_tweet": null }
+        })
+    );
+}
+
+#[async_std::test]
+async fn protected_tweets_cannot_be_amplified() {
+    let server = test_setup().await;
+    let alices_token = token_for("alice", &server).await;
+    let bobs_token = token_for("bob", &server).await;
+    follow("alice", &bobs_token, &server).await;
+    patch(
+        "/me",
+        Some(UpdateProfilePayload {
+            protected: Some(true),
+            ..Default::default()
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", alices_token))
+    .send(&server)
+    .await;
+    let id = tweet("Just for friends", &alices_token, &server).await;
+
+    let (json, status) = retweet(&id, &bobs_token, &server).await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "cannot_retweet_protected",
+                "message": "Protected tweets can't be retweeted",
+            }
+        })
+    );
+
+    let (json, status) = post_tweet(
+        CreateTweetPayload {
+            text: "Look".to_string(),
+            quoted_tweet_id: Some(id.parse().unwrap()),
+            ..Default::default()
+        },
+        &bobs_token,
+        &server,
+    )
+    .await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "cannot_retweet_protected",
+                "message": "Protected tweets can't be quoted",
+            }
+        })
+    );
+}
@@ -0,0 +1,78 @@
+use crate::routes;
+use crate::tests::test_helpers::*;
+
+// Every request a test sends is checked against the recorded routes, so this
+// only needs to show the check itself works.
+#[async_std::test]
+async fn endpoints_record_their_routes() {
+    let _server = test_setup().await;
+
+    assert!(routes::is_registered(Method::Get, "/me/timeline"));
+    assert!(routes::is_registered(Method::Post, "/users/alice/follow"));
+    assert!(routes::is_registered(Method::Get, "/blobs/avatars/some/key.png"));
+    assert!(!routes::is_registered(Method::Get, "/users/alice/follow"));
+    assert!(!routes::is_registered(Method::Get, "/users/alice/follow/extra"));
+}
+
+// tide can't list its routes, so each recorded path is asked for with every
+// method, and whatever tide routes has to be exactly what was recorded.
+#[async_std::test]
+async fn the_server_serves_exactly_the_recorded_routes() {
+    let server = test_setup().await;
+
+    let recorded = routes::recorded();
+    assert!(!recorded.is_empty());
+
+    for (_, url_spec) in recorded {
+        let path = example_path(url_spec);
+        for &method in &[
+            Method::Get,
+            Method::Post,
+            Method::Put,
+            Method::Patch,
+            Method::Delete,
+        ] {
+            assert_eq!(
+                is_routed(method, &path, &server).await,
+                routes::is_registered(method, &path),
+                "{} {}",
+                method,
+                path,
+            );
+        }
+    }
+
+    assert!(!is_routed(Method::Get, "/unrecorded", &server).await);
+}
+
+/// Fills in every `:param` and `*wildcard` so the router has something to
+/// match.
+fn example_path(url_spec: &str) -> String {
+    url_spec
+        .split('/')
+        .map(|segment| {
+            if segment.starts_with(':') || segment.starts_with('*') {
+                "x"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// A request tide can't route gets an empty 404 or 405, which
+/// `ErrorReponseToJson` fills in with a generic message. Anything else came
+/// from a handler. This goes straight to the server, since the routes it
+/// asks for aren't all recorded.
+async fn is_routed(method: Method, path: &str, server: &TestServer) -> bool {
+    let url = Url::parse(&format!("http://example.com{}", path)).unwrap();
+    let mut res = server.simulate(Request::new(method, url)).await.unwrap();
+    let status = res.status();
+    let body = res.body_bytes().await.unwrap();
+    let json = serde_json::from_slice::<Value>(&body).unwrap_or(Value::Null);
+
+    let unrouted = (status == StatusCode::NotFound || status == StatusCode::MethodNotAllowed)
+        && json["error"]["message"] == "Something went wrong";
+    !unrouted
+}
@@ -0,0 +1,187 @@
+use crate::tests::test_helpers::*;
+
+async fn login(username: &str, user_agent: &str, server: &TestServer) -> String {
+    let (json, status, _) = post(
+        &format!("/users/{}/session", username),
+        Some(LoginPayload {
+            password: "foobar".to_string(),
+        }),
+    )
+    .header("User-Agent", user_agent)
+    .header("X-Forwarded-For", "10.0.0.9")
+    .send(server)
+    .await;
+    assert_eq!(status, 201);
+
+    json["data"]["token"].as_str().unwrap().to_string()
+}
+
+#[async_std::test]
+async fn logging_out_on_one_device_keeps_the_others_signed_in() {
+    let server = test_setup().await;
+
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+    let laptop = login("bob", "laptop", &server).await;
+    let phone = login("bob", "phone", &server).await;
+    assert_ne!(laptop, phone);
+
+    let (_, status, _) = delete("/users/bob/session")
+        .header("Authorization", format!("Bearer {}", laptop))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", laptop))
+        .send(&server)
+        .await;
+    assert_eq!(status, 401);
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", phone))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn expired_sessions_are_rejected() {
+    use crate::clock::*;
+    use chrono::prelude::*;
+
+    let server = test_setup().await;
+
+    let time = Utc.ymd(1970, 1, 1).and_hms(0, 0, 0);
+    let token = freeze_time::<TokenResponse, _, _>(time, || async {
+        create_user_and_authenticate(&server, None).await
+    })
+    .await
+    .token;
+
+    let (json, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 401);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "invalid_token",
+                "message": "Invalid auth token"
+            }
+        })
+    );
+}
+
+#[async_std::test]
+async fn listing_sessions() {
+    let server = test_setup().await;
+
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+    login("bob", "laptop", &server).await;
+    let phone = login("bob", "phone", &server).await;
+
+    let (json, status, _) = get("/me/sessions")
+        .header("Authorization", format!("Bearer {}", phone))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    let sessions = serde_json::from_value::<ApiResponse<Vec<SessionResponse>>>(json)
+        .unwrap()
+        .data;
+    assert_eq!(sessions.len(), 3);
+
+    let current = sessions.iter().filter(|s| s.current).collect::<Vec<_>>();
+    assert_eq!(current.len(), 1);
+    assert_eq!(current[0].user_agent.as_deref(), Some("phone"));
+    assert_eq!(current[0].ip_address.as_deref(), Some("127.0.0.1"));
+    assert!(sessions
+        .iter()
+        .any(|s| s.user_agent.as_deref() == Some("laptop")));
+}
+
+#[async_std::test]
+async fn revoking_a_session() {
+    let server = test_setup().await;
+
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+    let laptop = login("bob", "laptop", &server).await;
+    let phone = login("bob", "phone", &server).await;
+
+    let (json, _, _) = get("/me/sessions")
+        .header("Authorization", format!("Bearer {}", phone))
+        .send(&server)
+        .await;
+    let sessions = serde_json::from_value::<ApiResponse<Vec<SessionResponse>>>(json)
+        .unwrap()
+        .data;
+    let laptop_session = sessions
+        .iter()
+        .find(|s| s.user_agent.as_deref() == Some("laptop"))
+        .unwrap();
+
+    let (_, status, _) = delete(&format!("/me/sessions/{}", laptop_session.id))
+        .header("Authorization", format!("Bearer {}", phone))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", laptop))
+        .send(&server)
+        .await;
+    assert_eq!(status, 401);
+}
+
+#[async_std::test]
+async fn cannot_revoke_someone_elses_session() {
+    let server = test_setup().await;
+
+    let bob = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alice = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+
+    let (json, _, _) = get("/me/sessions")
+        .header("Authorization", format!("Bearer {}", bob))
+        .send(&server)
+        .await;
+    let bobs_session = &serde_json::from_value::<ApiResponse<Vec<SessionResponse>>>(json)
+        .unwrap()
+        .data[0];
+
+    let (_, status, _) = delete(&format!("/me/sessions/{}", bobs_session.id))
+        .header("Authorization", format!("Bearer {}", alice))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", bob))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn tokens_are_not_stored_in_plain_text() {
+    use sqlx::Row;
+
+    let server = test_setup().await;
+
+    let token = create_user_and_authenticate(&server, None).await.token;
+
+    let rows = sqlx::query("select token_hash from auth_tokens")
+        .fetch_all(&server.db_pool())
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+
+    let stored = rows[0].get::<String, _>("token_hash");
+    assert_ne!(stored, token);
+    assert_eq!(stored, crate::tokens::hash(&token).unwrap());
+}
@@ -2,9 +2,13 @@
 
 mod test_db;
 
+use crate::config::Config;
+use crate::blobs::LocalBlobStore;
+use crate::mailer::FileMailer;
 use crate::Server;
 use crate::State;
 use crate::{make_db_pool, server};
+use chrono::prelude::*;
 use futures::{executor::block_on, prelude::*};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -15,8 +19,10 @@ use sqlx::Postgres;
 use std::collections::HashMap;
 use std::env;
 use std::pin::Pin;
+use std::sync::Arc;
 use test_db::TestDb;
 
+pub use crate::mailer::Email;
 pub use assert_json_diff::{assert_json_eq, assert_json_include};
 pub use serde_json::{json, Value};
 pub use shared::payloads::*;
@@ -29,7 +35,16 @@ pub use tide::{
     StatusCode,
 };
 
+/// Where every test request comes from, as far as the socket is concerned.
+pub const TEST_PEER_ADDR: &str = "127.0.0.1:50000";
+
 pub async fn test_setup() -> TestServer {
+    test_setup_with_config(|_| {}).await
+}
+
+/// Like `test_setup`, but lets the test change the config the server runs
+/// with.
+pub async fn test_setup_with_config(configure: impl FnOnce(&mut Config)) -> TestServer {
     std::env::set_var("APP_ENV", "test");
     dotenv::dotenv().ok();
 
@@ -38,23 +53,81 @@ pub async fn test_setup() -> TestServer {
     let test_db = TestDb::new().await;
     let db_pool = test_db.db();
 
-    let server = server(db_pool).await;
-    TestServer::new(server, test_db)
+    let mail_dir = env::temp_dir().join(format!("witter-test-mail-{}", uuid::Uuid::new_v4()));
+    let mailer = Arc::new(FileMailer::new(mail_dir));
+    let blob_dir = env::temp_dir().join(format!("witter-test-blobs-{}", uuid::Uuid::new_v4()));
+    let blobs = Arc::new(LocalBlobStore::new(blob_dir));
+
+    let mut config = Config::from_env();
+    configure(&mut config);
+
+    let state = State {
+        db_pool,
+        config,
+        mailer: mailer.clone(),
+        blobs: blobs.clone(),
+    };
+    let server = server(state).await;
+    TestServer::new(server, test_db, mailer, blobs)
 }
 
 pub struct TestServer {
     service: Server<State>,
     test_db: TestDb,
+    mailer: Arc<FileMailer>,
+    blobs: Arc<LocalBlobStore>,
 }
 
 impl TestServer {
-    fn new(service: Server<State>, test_db: TestDb) -> Self {
-        Self { service, test_db }
+    fn new(
+        service: Server<State>,
+        test_db: TestDb,
+        mailer: Arc<FileMailer>,
+        blobs: Arc<LocalBlobStore>,
+    ) -> Self {
+        Self {
+            service,
+            test_db,
+            mailer,
+            blobs,
+        }
+    }
+
+    pub async fn sent_emails(&self) -> Vec<Email> {
+        self.mailer.sent().await.unwrap()
+    }
+
+    /// For emails sent in the background after the response. Gives up after
+    /// a few seconds and returns whatever has been sent by then.
+    pub async fn wait_for_emails(&self, count: usize) -> Vec<Email> {
+        for _ in 0..100 {
+            let emails = self.sent_emails().await;
+            if emails.len() >= count {
+                return emails;
+            }
+            async_std::task::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        self.sent_emails().await
     }
 
     pub async fn simulate(&self, req: Request) -> tide::Result<Response> {
         self.service.respond(req).await
     }
+
+    pub fn db_pool(&self) -> PgPool {
+        self.test_db.db()
+    }
+
+    pub fn blobs(&self) -> &LocalBlobStore {
+        &self.blobs
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(self.mailer.dir());
+        let _ = std::fs::remove_dir_all(self.blobs.dir());
+    }
 }
 
 pub trait BodyJson {
@@ -84,10 +157,16 @@ pub fn get(url: &str) -> TestRequest {
 }
 
 pub fn delete(url: &str) -> TestRequest {
+    delete_with_body(url, None::<()>)
+}
+
+pub fn delete_with_body<T: Serialize>(url: &str, body: Option<T>) -> TestRequest {
+    let body = body.map(|body| serde_json::to_value(body).unwrap());
+
     TestRequest {
         url: url.to_string(),
         headers: HashMap::new(),
-        kind: TestRequestKind::Delete,
+        kind: TestRequestKind::Delete(body),
     }
 }
 
@@ -102,6 +181,47 @@ pub fn post<T: Serialize>(url: &str, body: Option<T>) -> TestRequest {
     }
 }
 
+pub fn put<T: Serialize>(url: &str, body: Option<T>) -> TestRequest {
+    let body = body.map(|body| serde_json::to_value(body).unwrap());
+
+    TestRequest {
+        url: url.to_string(),
+        headers: HashMap::new(),
+        kind: TestRequestKind::Put(body),
+    }
+}
+
+pub fn patch<T: Serialize>(url: &str, body: Option<T>) -> TestRequest {
+    let body = body.map(|body| serde_json::to_value(body).unwrap());
+
+    TestRequest {
+        url: url.to_string(),
+        headers: HashMap::new(),
+        kind: TestRequestKind::Patch(body),
+    }
+}
+
+/// Posts a `multipart/form-data` body with a single file part.
+pub fn post_file(url: &str, field: &str, content_type: &str, contents: &[u8]) -> TestRequest {
+    let boundary = format!("witter-test-{}", uuid::Uuid::new_v4());
+
+    let mut body = format!(
+        "--{}\r\n\
+         Content-Disposition: form-data; name=\"{}\"; filename=\"upload\"\r\n\
+         Content-Type: {}\r\n\r\n",
+        boundary, field, content_type,
+    )
+    .into_bytes();
+    body.extend_from_slice(contents);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+    TestRequest {
+        url: url.to_string(),
+        headers: HashMap::new(),
+        kind: TestRequestKind::Multipart(body, boundary),
+    }
+}
+
 pub fn empty_post(url: &str) -> TestRequest {
     post(url, None::<()>)
 }
@@ -116,8 +236,12 @@ pub struct TestRequest {
 #[derive(Debug)]
 pub enum TestRequestKind {
     Get,
-    Delete,
+    Delete(Option<Value>),
     Post(Option<Value>),
+    Put(Option<Value>),
+    Patch(Option<Value>),
+    /// The encoded body and its boundary.
+    Multipart(Vec<u8>, String),
 }
 
 impl TestRequest {
@@ -125,26 +249,53 @@ impl TestRequest {
         self,
         server: &TestServer,
     ) -> (Value, StatusCode, HashMap<String, String>) {
+        let (body, status, headers) = self.send_for_bytes(server).await;
+        let json = serde_json::from_slice::<Value>(&body).unwrap();
+
+        (json, status, headers)
+    }
+
+    /// Like `send`, for responses that aren't JSON.
+    pub async fn send_for_bytes(
+        self,
+        server: &TestServer,
+    ) -> (Vec<u8>, StatusCode, HashMap<String, String>) {
         let url = Url::parse(&format!("http://example.com{}", self.url)).unwrap();
 
-        let mut req = match self.kind {
-            TestRequestKind::Get => Request::new(Method::Get, url),
-            TestRequestKind::Post(body) => {
-                let mut req = Request::new(Method::Post, url);
-                if let Some(body) = body {
-                    req.set_body(body.to_string());
-                    req.set_content_type("application/json".parse().unwrap());
-                }
-                req
-            }
-            TestRequestKind::Delete => Request::new(Method::Delete, url),
+        let json = |body: Option<Value>| {
+            body.map(|body| (body.to_string().into_bytes(), "application/json".to_string()))
         };
+        let (method, body) = match self.kind {
+            TestRequestKind::Get => (Method::Get, None),
+            TestRequestKind::Post(body) => (Method::Post, json(body)),
+            TestRequestKind::Put(body) => (Method::Put, json(body)),
+            TestRequestKind::Patch(body) => (Method::Patch, json(body)),
+            TestRequestKind::Delete(body) => (Method::Delete, json(body)),
+            TestRequestKind::Multipart(body, boundary) => (
+                Method::Post,
+                Some((body, format!("multipart/form-data; boundary={}", boundary))),
+            ),
+        };
+
+        assert!(
+            crate::routes::is_registered(method, url.path()),
+            "{} {} isn't a route registered through add_endpoint",
+            method,
+            url.path(),
+        );
+
+        let mut req = Request::new(method, url);
+        req.set_peer_addr(Some(TEST_PEER_ADDR));
+        if let Some((body, content_type)) = body {
+            req.set_body(body);
+            req.set_content_type(content_type.parse().unwrap());
+        }
 
         for (key, value) in self.headers {
             req.append_header(key.as_str(), value.as_str());
         }
 
-        let res = server.simulate(req).await.unwrap();
+        let mut res = server.simulate(req).await.unwrap();
         let status = res.status();
         let headers = res
             .iter()
@@ -154,9 +305,9 @@ impl TestRequest {
                     .map(move |value| (key.as_str().to_string(), value.as_str().to_string()))
             })
             .collect::<HashMap<_, _>>();
-        let json = res.body_json::<Value>().await.unwrap();
+        let body = res.body_bytes().await.unwrap();
 
-        (json, status, headers)
+        (body, status, headers)
     }
 
     pub fn header(mut self, key: &str, value: impl ToString) -> Self {
@@ -174,6 +325,7 @@ pub async fn create_user_and_authenticate(
             Some(CreateUserPayload {
                 username: username.unwrap_or_else(|| "bob".to_string()),
                 password: "foobar".to_string(),
+                email: None,
             }),
         )
         .send(server)
@@ -183,4 +335,65 @@ pub async fn create_user_and_authenticate(
         serde_json::from_value::<ApiResponse<TokenResponse>>(json)
             .unwrap()
             .data
-}
\ No newline at end of file
+}
+/// Shorthand for signing up `username` and keeping only the token.
+pub async fn token_for(username: &str, server: &TestServer) -> String {
+    create_user_and_authenticate(server, Some(username.to_string()))
+        .await
+        .token
+}
+
+pub async fn post_tweet(
+    payload: CreateTweetPayload,
+    token: &str,
+    server: &TestServer,
+) -> (Value, StatusCode) {
+    let (json, status, _) = post("/tweets", Some(payload))
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    (json, status)
+}
+
+/// Posts a plain tweet that's expected to go through and returns its id.
+pub async fn tweet(text: &str, token: &str, server: &TestServer) -> String {
+    let (json, status) = post_tweet(
+        CreateTweetPayload {
+            text: text.to_string(),
+            ..Default::default()
+        },
+        token,
+        server,
+    )
+    .await;
+    assert_eq!(status, 201);
+    json["data"]["id"].as_str().unwrap().to_string()
+}
+
+pub async fn follow(username: &str, token: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = empty_post(&format!("/users/{}/follow", username))
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    (json, status)
+}
+
+/// The text of each tweet in the timeline of whoever `token` belongs to.
+pub async fn timeline_texts(token: &str, server: &TestServer) -> Vec<String> {
+    let (json, status, _) = get("/me/timeline")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    assert_eq!(status, 200);
+    json["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tweet| tweet["text"].as_str().unwrap().to_string())
+        .collect()
+}
+
+/// A fixed point to freeze time at, for tests that move the clock around.
+pub fn start() -> DateTime<Utc> {
+    Utc.ymd(2020, 1, 1).and_hms(12, 0, 0)
+}
@@ -18,9 +18,9 @@ impl TestDb {
         
         let db_url = db_url();
         create_db(&db_url).await;
-        run_migrations(&db_url).await;
 
         let db_pool = PgPool::new(&db_url).await.unwrap();
+        crate::migrations::migrate(&db_pool).await.unwrap();
 
         Self {
             db_url,
@@ -62,7 +62,7 @@ fn parse_db_url(db_url: &str) -> (&str, &str) {
 }
 
 async fn create_db(db_url: &str) {
-    let (pg_conn, db_name) = dbg!(parse_db_url(db_url));
+    let (pg_conn, db_name) = parse_db_url(db_url);
 
     let mut conn = PgConnection::connect(pg_conn).await.unwrap();
 
@@ -99,23 +99,3 @@ AND pid <> pg_backend_pid();"#,
         .await
         .unwrap();
 }
-
-async fn run_migrations(db_url: &str) {
-    let (pg_conn, db_name) = parse_db_url(db_url);
-    let mut conn = PgConnection::connect(&format!("{}/{}", pg_conn, db_name))
-        .await
-        .unwrap();
-
-    // Run the migrations
-    let sql = async_std::fs::read_to_string("../bin/backend/setup.sql")
-        .await
-        .unwrap();
-
-    for query in sql.split(';') {
-        sqlx::query::<Postgres>(&query)
-            .execute(&mut conn)
-            .await
-            .unwrap();
-    }
-}
-
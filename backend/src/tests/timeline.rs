@@ -6,9 +6,9 @@ async fn sees_own_tweets() {
 
     let token = create_user_and_authenticate(&mut server, None).await.token;
 
-    post_tweet("oldest", &token, &mut server).await;
-    post_tweet("middle", &token, &mut server).await;
-    post_tweet("newest", &token, &mut server).await;
+    tweet("oldest", &token, &mut server).await;
+    tweet("middle", &token, &mut server).await;
+    tweet("newest", &token, &mut server).await;
 
     let (json, status, _) = get("/me/timeline")
     .header("Authorization", format!("Bearer {}", token))
@@ -39,9 +39,9 @@ async fn sees_tweets_from_users_we_are_following() {
         .await
         .token;
 
-    post_tweet("oldest", &alice_token, &mut server).await;
-    post_tweet("middle", &alice_token, &mut server).await;
-    post_tweet("newest", &alice_token, &mut server).await;
+    tweet("oldest", &alice_token, &mut server).await;
+    tweet("middle", &alice_token, &mut server).await;
+    tweet("newest", &alice_token, &mut server).await;
 
     let (_ , status, _) = empty_post("/users/alice/follow")
     .header("Authorization", format!("Bearer {}", bob_token))
@@ -73,11 +73,11 @@ async fn pagination() {
 
     let token = create_user_and_authenticate(&mut server, None).await.token;
 
-    post_tweet("5", &token, &mut server).await;
-    post_tweet("4", &token, &mut server).await;
-    post_tweet("3", &token, &mut server).await;
-    post_tweet("2", &token, &mut server).await;
-    post_tweet("1", &token, &mut server).await;
+    tweet("5", &token, &mut server).await;
+    tweet("4", &token, &mut server).await;
+    tweet("3", &token, &mut server).await;
+    tweet("2", &token, &mut server).await;
+    tweet("1", &token, &mut server).await;
 
     // page 1
     let (json, status, _) = get("/me/timeline?page=1&page_size=2")
@@ -140,7 +140,7 @@ async fn max_page_size() {
     let token = create_user_and_authenticate(&mut server, None).await.token;
 
     for _ in 0..21 {
-        post_tweet("hi", &token, &mut server).await;
+        tweet("hi", &token, &mut server).await;
     }
 
     let (json, status, _) = get("/me/timeline?page=1&page_size=20")
@@ -153,16 +153,29 @@ async fn max_page_size() {
 
 }
 
-async fn post_tweet(text: &str, token: &str, server: &TestServer) {
-    post(
-        "/tweets",
-        Some(CreateTweetPayload {
-            text: text.to_string(),
-        }),
-    )
-    .header("Authorization", format!("Bearer {}", token))
-    .send(server)
-    .await;
+#[async_std::test]
+async fn out_of_range_pagination_is_clamped() {
+    let server = test_setup().await;
+
+    let token = create_user_and_authenticate(&server, None).await.token;
+
+    for _ in 0..2 {
+        tweet("hi", &token, &server).await;
+    }
+
+    let (json, status, _) = get("/me/timeline?page=1&page_size=0")
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+
+    let (json, status, _) = get(&format!("/me/timeline?page={}&page_size=100", usize::MAX))
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert!(json["data"].as_array().unwrap().is_empty());
 }
 
 #[async_std::test]
@@ -176,7 +189,7 @@ async fn response_includes_user_who_posted_tweet() {
 
     let time = Utc.ymd(1970, 1, 1).and_hms(0, 1, 1);
     freeze_time::<(), _, _>(time, || async {
-        post_tweet("foo", &token, &server).await;
+        tweet("foo", &token, &server).await;
     }).await;
 
     let (json, status, _) = get("/me/timeline")
@@ -0,0 +1,178 @@
+use crate::clock::*;
+use crate::tests::test_helpers::*;
+use chrono::Duration;
+
+async fn edit_tweet(id: &str, text: &str, token: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = patch(
+        &format!("/tweets/{}", id),
+        Some(EditTweetPayload {
+            text: text.to_string(),
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(server)
+    .await;
+    (json, status)
+}
+
+#[async_std::test]
+async fn editing_a_tweet_keeps_its_history() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let id = freeze_time::<String, _, _>(start(), || async {
+        tweet("Helo, World!", &token, &server).await
+    })
+    .await;
+
+    let (json, _, _) = get(&format!("/tweets/{}", id)).send(&server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": { "edited_at": null, "revision_count": 0 } })
+    );
+
+    let (json, status) = freeze_time::<(Value, StatusCode), _, _>(
+        start() + Duration::minutes(5),
+        || async { edit_tweet(&id, "Hello, World!", &token, &server).await },
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "id": id,
+                "text": "Hello, World!",
+                "created_at": start(),
+                "edited_at": start() + Duration::minutes(5),
+                "revision_count": 1,
+                "user": { "username": "bob" },
+            }
+        })
+    );
+
+    freeze_time::<(), _, _>(start() + Duration::minutes(10), || async {
+        edit_tweet(&id, "Hello, World!!", &token, &server).await;
+    })
+    .await;
+
+    let (json, status, _) = get(&format!("/tweets/{}/history", id)).send(&server).await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": [
+                { "text": "Hello, World!", "created_at": start() + Duration::minutes(5) },
+                { "text": "Helo, World!", "created_at": start() },
+            ]
+        })
+    );
+    assert_eq!(json["data"].as_array().unwrap().len(), 2);
+}
+
+#[async_std::test]
+async fn saving_the_same_text_is_not_an_edit() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let id = tweet("Hello, World!", &token, &server).await;
+
+    let (json, status) = edit_tweet(&id, "Hello, World!", &token, &server).await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": { "edited_at": null, "revision_count": 0 } })
+    );
+
+    let (json, _, _) = get(&format!("/tweets/{}/history", id)).send(&server).await;
+    assert_json_eq!(json, json!({ "data": [] }));
+}
+
+#[async_std::test]
+async fn only_the_author_can_edit() {
+    let server = test_setup().await;
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    let id = tweet("Hello, World!", &bobs_token, &server).await;
+
+    let (json, status) = edit_tweet(&id, "Hacked", &alices_token, &server).await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "forbidden",
+                "message": "You can only edit your own tweets",
+            }
+        })
+    );
+
+    let unknown_id = uuid::Uuid::new_v4().to_string();
+    let (json, status) = edit_tweet(&unknown_id, "Hi", &bobs_token, &server).await;
+    assert_eq!(status, 404);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "tweet_not_found" } })
+    );
+}
+
+#[async_std::test]
+async fn edits_are_only_allowed_within_the_window() {
+    let server = test_setup_with_config(|config| {
+        config.tweet_edit_window = Duration::minutes(10)
+    })
+    .await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let id = freeze_time::<String, _, _>(start(), || async {
+        tweet("Hello, World!", &token, &server).await
+    })
+    .await;
+
+    let (json, status) = freeze_time::<(Value, StatusCode), _, _>(
+        start() + Duration::minutes(11),
+        || async { edit_tweet(&id, "Too late", &token, &server).await },
+    )
+    .await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "edit_window_closed",
+                "message": "Tweets can only be edited for 10 minutes after posting",
+            }
+        })
+    );
+
+    let (json, _, _) = get(&format!("/tweets/{}", id)).send(&server).await;
+    assert_json_include!(actual: json, expected: json!({ "data": { "text": "Hello, World!" } }));
+}
+
+#[async_std::test]
+async fn edits_cannot_make_a_tweet_too_long() {
+    use shared::MAX_TWEET_LENGTH;
+
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let id = tweet("Hello, World!", &token, &server).await;
+
+    let text = std::iter::repeat('a').take(MAX_TWEET_LENGTH + 1).collect::<String>();
+    let (json, status) = edit_tweet(&id, &text, &token, &server).await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "tweet_too_long" } })
+    );
+}
@@ -0,0 +1,124 @@
+use crate::tests::test_helpers::*;
+
+#[async_std::test]
+async fn getting_a_tweet() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let id = tweet("Hello, World!", &token, &server).await;
+
+    let (json, status, _) = get(&format!("/tweets/{}", id)).send(&server).await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "id": id,
+                "text": "Hello, World!",
+                "user": { "username": "bob" },
+            }
+        })
+    );
+}
+
+#[async_std::test]
+async fn getting_unknown_tweets() {
+    let server = test_setup().await;
+
+    for url in &[
+        format!("/tweets/{}", uuid::Uuid::new_v4()),
+        "/tweets/not-an-id".to_string(),
+    ] {
+        let (json, status, _) = get(url).send(&server).await;
+        assert_eq!(status, 404);
+        assert_json_include!(
+            actual: json,
+            expected: json!({ "error": { "code": "tweet_not_found" } })
+        );
+    }
+}
+
+#[async_std::test]
+async fn protected_tweets_need_a_follow() {
+    let server = test_setup().await;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    patch(
+        "/me",
+        Some(UpdateProfilePayload {
+            protected: Some(true),
+            ..Default::default()
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", alices_token))
+    .send(&server)
+    .await;
+    let id = tweet("Just for friends", &alices_token, &server).await;
+
+    let (json, status, _) = get(&format!("/tweets/{}", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "error": { "code": "protected_account" } })
+    );
+
+    let (_, status, _) = get(&format!("/tweets/{}", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn deleting_a_tweet() {
+    let server = test_setup().await;
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    let id = tweet("Oops", &bobs_token, &server).await;
+
+    let (json, status, _) = delete(&format!("/tweets/{}", id))
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 403);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "code": "forbidden",
+                "message": "You can only delete your own tweets",
+            }
+        })
+    );
+
+    let (json, status, _) = delete(&format!("/tweets/{}", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_json_eq!(json, json!({ "data": null }));
+
+    let (_, status, _) = get(&format!("/tweets/{}", id)).send(&server).await;
+    assert_eq!(status, 404);
+
+    let (json, _, _) = get("/users/bob").send(&server).await;
+    assert_json_include!(actual: json, expected: json!({ "data": { "tweets_count": 0 } }));
+
+    let (_, status, _) = delete(&format!("/tweets/{}", id))
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_eq!(status, 404);
+}
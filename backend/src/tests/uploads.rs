@@ -0,0 +1,236 @@
+use crate::accounts;
+use crate::clock::*;
+use crate::config::Config;
+use crate::tests::test_helpers::*;
+use chrono::Duration;
+use image::{DynamicImage, GenericImageView, ImageOutputFormat};
+use shared::MAX_IMAGE_UPLOAD_SIZE;
+use std::collections::HashMap;
+
+fn encode(width: u32, height: u32, format: ImageOutputFormat) -> Vec<u8> {
+    let image = DynamicImage::new_rgb8(width, height);
+    let mut bytes = Vec::new();
+    image.write_to(&mut bytes, format).unwrap();
+    bytes
+}
+
+/// A JPEG with an EXIF segment holding a (fake) GPS position.
+fn jpeg_with_exif() -> Vec<u8> {
+    let jpeg = encode(800, 800, ImageOutputFormat::Jpeg(90));
+
+    let payload = b"Exif\0\0GPS 55.6761 N 12.5683 E";
+    let length = (payload.len() + 2) as u16;
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&length.to_be_bytes());
+    segment.extend_from_slice(payload);
+
+    // Right after the SOI marker
+    let mut bytes = jpeg[..2].to_vec();
+    bytes.extend(segment);
+    bytes.extend_from_slice(&jpeg[2..]);
+    bytes
+}
+
+async fn upload(
+    url: &str,
+    content_type: &str,
+    contents: &[u8],
+    token: &str,
+    server: &TestServer,
+) -> (Value, StatusCode) {
+    let (json, status, _) = post_file(url, "image", content_type, contents)
+        .header("Authorization", format!("Bearer {}", token))
+        .send(server)
+        .await;
+    (json, status)
+}
+
+/// Fetches a blob by the URL the API handed out.
+async fn fetch(url: &Value, server: &TestServer) -> (Vec<u8>, StatusCode, HashMap<String, String>) {
+    let url = url.as_str().unwrap();
+    let path = &url[url.find("/blobs/").unwrap()..];
+    get(path).send_for_bytes(server).await
+}
+
+#[async_std::test]
+async fn uploading_an_avatar() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let png = encode(1000, 600, ImageOutputFormat::Png);
+    let (json, status) = upload("/me/avatar", "image/png", &png, &token, &server).await;
+    assert_eq!(status, 201);
+
+    let (full, status, headers) = fetch(&json["data"]["avatar_url"], &server).await;
+    assert_eq!(status, 200);
+    assert_eq!(headers["content-type"], "image/jpeg");
+    assert_eq!(headers["cache-control"], "public, max-age=31536000, immutable");
+    assert_eq!(image::load_from_memory(&full).unwrap().dimensions(), (400, 400));
+
+    let (thumbnail, _, _) = fetch(&json["data"]["avatar_thumbnail_url"], &server).await;
+    assert_eq!(image::load_from_memory(&thumbnail).unwrap().dimensions(), (48, 48));
+
+    let (user, _, _) = get("/users/bob").send(&server).await;
+    assert_eq!(user["data"]["avatar_url"], json["data"]["avatar_url"]);
+    assert_eq!(
+        user["data"]["avatar_thumbnail_url"],
+        json["data"]["avatar_thumbnail_url"]
+    );
+}
+
+#[async_std::test]
+async fn uploading_a_header() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let png = encode(3000, 1000, ImageOutputFormat::Png);
+    let (json, status) = upload("/me/header", "image/png", &png, &token, &server).await;
+    assert_eq!(status, 201);
+
+    let (header, status, _) = fetch(&json["data"]["header_url"], &server).await;
+    assert_eq!(status, 200);
+    assert_eq!(image::load_from_memory(&header).unwrap().dimensions(), (1500, 500));
+}
+
+#[async_std::test]
+async fn exif_data_is_stripped() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let jpeg = jpeg_with_exif();
+    assert!(jpeg.windows(4).any(|window| window == b"Exif"));
+
+    let (json, status) = upload("/me/avatar", "image/jpeg", &jpeg, &token, &server).await;
+    assert_eq!(status, 201);
+
+    let (full, _, _) = fetch(&json["data"]["avatar_url"], &server).await;
+    assert!(!full.windows(4).any(|window| window == b"Exif"));
+    assert!(!full.windows(3).any(|window| window == b"GPS"));
+}
+
+#[async_std::test]
+async fn only_images_are_accepted() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let (json, status) = upload("/me/avatar", "text/plain", b"hello", &token, &server).await;
+    assert_eq!(status, 422);
+    assert_eq!(json["error"]["code"], "unsupported_image");
+
+    // Claiming to be something it isn't
+    let png = encode(100, 100, ImageOutputFormat::Png);
+    let (json, status) = upload("/me/avatar", "image/jpeg", &png, &token, &server).await;
+    assert_eq!(status, 422);
+    assert_eq!(json["error"]["code"], "unsupported_image");
+}
+
+#[async_std::test]
+async fn large_uploads_are_rejected() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let contents = vec![0; MAX_IMAGE_UPLOAD_SIZE + 1];
+    let (json, status) = upload("/me/avatar", "image/png", &contents, &token, &server).await;
+    assert_eq!(status, 413);
+    assert_eq!(json["error"]["code"], "image_too_large");
+}
+
+#[async_std::test]
+async fn replacing_an_avatar_removes_the_old_files() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let png = encode(100, 100, ImageOutputFormat::Png);
+    let (first, _) = upload("/me/avatar", "image/png", &png, &token, &server).await;
+    let (second, _) = upload("/me/avatar", "image/png", &png, &token, &server).await;
+    assert_ne!(first["data"]["avatar_url"], second["data"]["avatar_url"]);
+
+    let (_, status, _) = fetch(&first["data"]["avatar_url"], &server).await;
+    assert_eq!(status, 404);
+    let (_, status, _) = fetch(&second["data"]["avatar_url"], &server).await;
+    assert_eq!(status, 200);
+}
+
+#[async_std::test]
+async fn linking_an_avatar_removes_the_uploaded_files() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let png = encode(100, 100, ImageOutputFormat::Png);
+    let (uploaded, _) = upload("/me/avatar", "image/png", &png, &token, &server).await;
+
+    let (_, status, _) = patch(
+        "/me",
+        Some(UpdateProfilePayload {
+            avatar_url: Some("https://bob.example.com/me.png".to_string()),
+            ..Default::default()
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", token))
+    .send(&server)
+    .await;
+    assert_eq!(status, 200);
+
+    for url in &[
+        &uploaded["data"]["avatar_url"],
+        &uploaded["data"]["avatar_thumbnail_url"],
+    ] {
+        let (_, status, _) = fetch(url, &server).await;
+        assert_eq!(status, 404);
+    }
+}
+
+#[async_std::test]
+async fn purging_an_account_removes_its_files() {
+    let server = test_setup().await;
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+
+    let png = encode(100, 100, ImageOutputFormat::Png);
+    let (avatar, _) = upload("/me/avatar", "image/png", &png, &token, &server).await;
+    let (header, _) = upload("/me/header", "image/png", &png, &token, &server).await;
+
+    freeze_time::<(), _, _>(start(), || async {
+        delete_with_body(
+            "/me",
+            Some(DeleteAccountPayload {
+                password: "foobar".to_string(),
+            }),
+        )
+        .header("Authorization", format!("Bearer {}", token))
+        .send(&server)
+        .await;
+    })
+    .await;
+
+    freeze_time::<(), _, _>(start() + Duration::days(15), || async {
+        accounts::purge_expired(&server.db_pool(), &Config::from_env(), server.blobs())
+            .await
+            .unwrap();
+    })
+    .await;
+
+    for url in &[
+        &avatar["data"]["avatar_url"],
+        &avatar["data"]["avatar_thumbnail_url"],
+        &header["data"]["header_url"],
+    ] {
+        let (_, status, _) = fetch(url, &server).await;
+        assert_eq!(status, 404);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::tests::test_helpers::*;
+
+#[async_std::test]
+async fn users_include_counts() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    create_user_and_authenticate(&server, Some("carol".to_string())).await;
+
+    follow("alice", &bobs_token, &server).await;
+    follow("carol", &bobs_token, &server).await;
+    follow("bob", &alices_token, &server).await;
+    for text in &["one", "two"] {
+        tweet(text, &bobs_token, &server).await;
+    }
+
+    let (json, status, _) = get("/users/bob").send(&server).await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": {
+                "followers_count": 1,
+                "following_count": 2,
+                "tweets_count": 2,
+            }
+        })
+    );
+
+    // Anonymous requests have no point of view
+    assert!(json["data"].get("followed_by_me").is_none());
+    assert!(json["data"].get("follows_me").is_none());
+
+    delete("/users/carol/follow")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+
+    let (json, _, _) = get("/users/bob").send(&server).await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": { "following_count": 1 } })
+    );
+}
+
+/// `(followers_count, following_count)`
+async fn follow_counts(username: &str, server: &TestServer) -> (Value, Value) {
+    let (json, _, _) = get(&format!("/users/{}", username)).send(server).await;
+    (
+        json["data"]["followers_count"].clone(),
+        json["data"]["following_count"].clone(),
+    )
+}
+
+#[async_std::test]
+async fn deactivated_users_are_not_counted() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    follow("alice", &bobs_token, &server).await;
+    follow("bob", &alices_token, &server).await;
+    assert_eq!(follow_counts("alice", &server).await, (json!(1), json!(1)));
+
+    delete_with_body(
+        "/me",
+        Some(DeleteAccountPayload {
+            password: "foobar".to_string(),
+        }),
+    )
+    .header("Authorization", format!("Bearer {}", bobs_token))
+    .send(&server)
+    .await;
+    assert_eq!(follow_counts("alice", &server).await, (json!(0), json!(0)));
+
+    // Logging in during the grace period brings the account back
+    let (_, status, _) = post(
+        "/users/bob/session",
+        Some(LoginPayload {
+            password: "foobar".to_string(),
+        }),
+    )
+    .send(&server)
+    .await;
+    assert_eq!(status, 201);
+    assert_eq!(follow_counts("alice", &server).await, (json!(1), json!(1)));
+}
+
+#[async_std::test]
+async fn users_include_relationship_to_viewer() {
+    let server = test_setup().await;
+
+    let bobs_token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    let alices_token = create_user_and_authenticate(&server, Some("alice".to_string()))
+        .await
+        .token;
+    create_user_and_authenticate(&server, Some("carol".to_string())).await;
+
+    follow("alice", &bobs_token, &server).await;
+    follow("carol", &alices_token, &server).await;
+
+    let (json, _, _) = get("/users/alice")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": { "followed_by_me": true, "follows_me": false } })
+    );
+
+    let (json, _, _) = get("/users/bob")
+        .header("Authorization", format!("Bearer {}", alices_token))
+        .send(&server)
+        .await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": { "followed_by_me": false, "follows_me": true } })
+    );
+
+    let (json, _, _) = get("/users/alice/following")
+        .header("Authorization", format!("Bearer {}", bobs_token))
+        .send(&server)
+        .await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": [
+                { "username": "carol", "followed_by_me": false, "follows_me": false }
+            ]
+        })
+    );
+}
+
+#[async_std::test]
+async fn followers_are_paginated() {
+    let server = test_setup().await;
+
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+    for username in &["alice", "carol", "dave"] {
+        let token = create_user_and_authenticate(&server, Some(username.to_string()))
+            .await
+            .token;
+        follow("bob", &token, &server).await;
+    }
+
+    let (json, status, _) = get("/users/bob/followers?page=1&page_size=2")
+        .send(&server)
+        .await;
+    assert_eq!(status, 200);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "data": [
+                { "username": "dave" },
+                { "username": "carol" },
+            ]
+        })
+    );
+    assert_eq!(json["data"].as_array().unwrap().len(), 2);
+
+    let (json, _, _) = get("/users/bob/followers?page=2&page_size=2")
+        .send(&server)
+        .await;
+    assert_json_include!(
+        actual: json,
+        expected: json!({ "data": [{ "username": "alice" }] })
+    );
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+}
@@ -33,14 +33,15 @@ async fn creating_a_user_and_logging_in() {
     .send(&mut server)
     .await;
     assert_eq!(status, 201);
-    assert_json_include!(
-        actual: json,
-        expected: json!({
-            "data": {
-                "token": token
-            }
-        })
-    );
+
+    let login_token = json["data"]["token"].as_str().unwrap();
+    assert_ne!(login_token, token);
+
+    let (_, status, _) = get("/me")
+        .header("Authorization", format!("Bearer {}", login_token))
+        .send(&mut server)
+        .await;
+    assert_eq!(status, 200);
 }
 
 #[async_std::test]
@@ -56,6 +57,7 @@ async fn claiming_username_already_claimed_gives_client_error() {
         Some(CreateUserPayload {
             username,
             password: "bar".to_string(),
+            email: None,
         }),
     )
     .send(&mut server)
@@ -71,3 +73,31 @@ async fn claiming_username_already_claimed_gives_client_error() {
         })
     );
 }
+
+#[async_std::test]
+async fn claimed_usernames_can_be_concealed() {
+    let server = test_setup_with_config(|config| config.conceal_claimed_usernames = true).await;
+
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+
+    let (json, status, _) = post(
+        "/users",
+        Some(CreateUserPayload {
+            username: "bob".to_string(),
+            password: "bar".to_string(),
+            email: None,
+        }),
+    )
+    .send(&server)
+    .await;
+
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "message": "An account can't be created with these details"
+            }
+        })
+    );
+}
@@ -0,0 +1,172 @@
+use crate::tests::test_helpers::*;
+
+async fn sign_up(username: &str, server: &TestServer) -> (Value, StatusCode) {
+    let (json, status, _) = post(
+        "/users",
+        Some(CreateUserPayload {
+            username: username.to_string(),
+            password: "foobar".to_string(),
+            email: None,
+        }),
+    )
+    .send(server)
+    .await;
+    (json, status)
+}
+
+#[async_std::test]
+async fn errors_are_grouped_by_field() {
+    let server = test_setup().await;
+
+    let (json, status) = sign_up("", &server).await;
+    assert_eq!(status, 422);
+    assert_json_eq!(
+        json,
+        json!({
+            "error": {
+                "status_code": "422",
+                "code": "validation_failed",
+                "message": "Username can't be blank",
+                "errors": {
+                    "username": ["can't be blank"]
+                }
+            }
+        })
+    );
+}
+
+#[async_std::test]
+async fn rejects_characters_that_break_urls() {
+    let server = test_setup().await;
+
+    for username in &["bob smith", "bob/smith", "bob.smith", "bøb"] {
+        let (json, status) = sign_up(username, &server).await;
+        assert_eq!(status, 422);
+        assert_json_include!(
+            actual: json,
+            expected: json!({
+                "error": {
+                    "errors": {
+                        "username": ["can only contain letters, numbers and underscores"]
+                    }
+                }
+            })
+        );
+    }
+}
+
+#[async_std::test]
+async fn enforces_length_bounds() {
+    use shared::{MAX_USERNAME_LENGTH, MIN_USERNAME_LENGTH};
+
+    let server = test_setup().await;
+
+    let (json, status) = sign_up("ab", &server).await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "errors": {
+                    "username": [
+                        format!("is too short (minimum is {} characters)", MIN_USERNAME_LENGTH)
+                    ]
+                }
+            }
+        })
+    );
+
+    let too_long = std::iter::repeat('a')
+        .take(MAX_USERNAME_LENGTH + 1)
+        .collect::<String>();
+    let (json, status) = sign_up(&too_long, &server).await;
+    assert_eq!(status, 422);
+    assert_json_include!(
+        actual: json,
+        expected: json!({
+            "error": {
+                "errors": {
+                    "username": [
+                        format!("is too long (maximum is {} characters)", MAX_USERNAME_LENGTH)
+                    ]
+                }
+            }
+        })
+    );
+
+    let longest = std::iter::repeat('a')
+        .take(MAX_USERNAME_LENGTH)
+        .collect::<String>();
+    let (_, status) = sign_up(&longest, &server).await;
+    assert_eq!(status, 201);
+}
+
+#[async_std::test]
+async fn rejects_reserved_names() {
+    let server = test_setup().await;
+
+    for username in &["admin", "login", "Settings", "tweets"] {
+        let (json, status) = sign_up(username, &server).await;
+        assert_eq!(status, 422);
+        assert_json_include!(
+            actual: json,
+            expected: json!({
+                "error": {
+                    "errors": {
+                        "username": ["is reserved"]
+                    }
+                }
+            })
+        );
+    }
+}
+
+#[async_std::test]
+async fn usernames_are_unique_regardless_of_case() {
+    let server = test_setup().await;
+
+    create_user_and_authenticate(&server, Some("bob".to_string())).await;
+
+    let (json, status) = sign_up("BoB", &server).await;
+    assert_eq!(status, 422);
+    assert_json_eq!(
+        json,
+        json!({
+            "error": {
+                "status_code": "422",
+                "code": "username_taken",
+                "message": "Username is already claimed",
+                "errors": {
+                    "username": ["is already claimed"]
+                }
+            }
+        })
+    );
+}
+
+#[async_std::test]
+async fn usernames_are_looked_up_regardless_of_case() {
+    let server = test_setup().await;
+
+    let token = create_user_and_authenticate(&server, Some("bob".to_string()))
+        .await
+        .token;
+    create_user_and_authenticate(&server, Some("alice".to_string())).await;
+
+    let (json, status, _) = get("/users/BoB").send(&server).await;
+    assert_eq!(status, 200);
+    assert_json_include!(actual: json, expected: json!({ "data": { "username": "bob" } }));
+
+    let (_, status, _) = post(
+        "/users/BOB/session",
+        Some(LoginPayload {
+            password: "foobar".to_string(),
+        }),
+    )
+    .send(&server)
+    .await;
+    assert_eq!(status, 201);
+
+    let (_, status) = follow("Alice", &token, &server).await;
+    assert_eq!(status, 201);
+}
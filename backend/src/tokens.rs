@@ -0,0 +1,23 @@
+use hmac::{Hmac, Mac, NewMac};
+use rand::distributions::Alphanumeric;
+use rand::rngs::OsRng;
+use rand::Rng;
+use sha2::Sha256;
+
+/// A fresh random bearer token. Only its hash is ever stored, so this is the
+/// one chance to hand it to the client.
+pub fn generate() -> String {
+    OsRng.sample_iter(&Alphanumeric).take(32).collect()
+}
+
+/// Hex encoded HMAC-SHA256 of `raw_token`, keyed with `SECRET_KEY`.
+///
+/// This has to agree with the `hmac(token, secret, 'sha256')` used by the
+/// `0003_hash_auth_tokens` migration.
+pub fn hash(raw_token: &str) -> tide::Result<String> {
+    let secret_key = std::env::var("SECRET_KEY")?;
+    let mut mac = Hmac::<Sha256>::new_varkey(secret_key.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(raw_token.as_bytes());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
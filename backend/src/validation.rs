@@ -0,0 +1,217 @@
+use chrono::{DateTime, Utc};
+use shared::errors::ErrorCode;
+use shared::payloads::UpdateProfilePayload;
+use shared::{
+    MAX_BIO_LENGTH, MAX_DISPLAY_NAME_LENGTH, MAX_LOCATION_LENGTH, MAX_MUTED_KEYWORD_LENGTH,
+    MAX_URL_LENGTH, MAX_USERNAME_LENGTH, MIN_USERNAME_LENGTH,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+use tide::http::Url;
+use tide::StatusCode;
+
+/// Names that would be confusing or collide with routes, either on the
+/// backend or in the frontend.
+const RESERVED_USERNAMES: &[&str] = &[
+    "about",
+    "admin",
+    "administrator",
+    "api",
+    "help",
+    "login",
+    "logout",
+    "me",
+    "null",
+    "password_resets",
+    "root",
+    "settings",
+    "sign_up",
+    "signed_in",
+    "signup",
+    "support",
+    "system",
+    "tweets",
+    "users",
+    "witter",
+];
+
+/// Problems with a payload, grouped by field. Carried inside a `tide::Error`
+/// and serialized by `ErrorReponseToJson` as `error.errors`.
+#[derive(Debug)]
+pub struct FieldErrors {
+    code: ErrorCode,
+    fields: BTreeMap<&'static str, Vec<String>>,
+}
+
+impl Default for FieldErrors {
+    fn default() -> Self {
+        Self {
+            code: ErrorCode::ValidationFailed,
+            fields: BTreeMap::new(),
+        }
+    }
+}
+
+impl FieldErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reports a more specific code than `validation_failed`.
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn add(&mut self, field: &'static str, message: impl Into<String>) {
+        self.fields.entry(field).or_default().push(message.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn to_map(&self) -> shared::errors::FieldErrors {
+        self.fields
+            .iter()
+            .map(|(field, messages)| (field.to_string(), messages.clone()))
+            .collect()
+    }
+
+    /// `Ok` if nothing was added, otherwise a 422 carrying the errors.
+    pub fn into_result(self) -> tide::Result<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(tide::Error::new(StatusCode::UnprocessableEntity, self))
+        }
+    }
+}
+
+/// Reads as a sentence per error, e.g. "Username is already claimed".
+impl fmt::Display for FieldErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sentences = self
+            .fields
+            .iter()
+            .flat_map(|(field, messages)| {
+                messages
+                    .iter()
+                    .map(move |message| format!("{} {}", humanize(field), message))
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", sentences.join(", "))
+    }
+}
+
+impl std::error::Error for FieldErrors {}
+
+pub fn validate_username(username: &str, errors: &mut FieldErrors) {
+    if username.is_empty() {
+        errors.add("username", "can't be blank");
+        return;
+    }
+
+    let length = username.chars().count();
+    if length < MIN_USERNAME_LENGTH {
+        errors.add(
+            "username",
+            format!("is too short (minimum is {} characters)", MIN_USERNAME_LENGTH),
+        );
+    }
+    if length > MAX_USERNAME_LENGTH {
+        errors.add(
+            "username",
+            format!("is too long (maximum is {} characters)", MAX_USERNAME_LENGTH),
+        );
+    }
+
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        errors.add("username", "can only contain letters, numbers and underscores");
+    }
+
+    if RESERVED_USERNAMES.contains(&username.to_lowercase().as_str()) {
+        errors.add("username", "is reserved");
+    }
+}
+
+/// Checks the fields that are being set. Empty strings clear a field, so
+/// they're always fine.
+pub fn validate_profile(payload: &UpdateProfilePayload, errors: &mut FieldErrors) {
+    let fields = [
+        ("display_name", &payload.display_name, MAX_DISPLAY_NAME_LENGTH),
+        ("bio", &payload.bio, MAX_BIO_LENGTH),
+        ("location", &payload.location, MAX_LOCATION_LENGTH),
+        ("website", &payload.website, MAX_URL_LENGTH),
+        ("avatar_url", &payload.avatar_url, MAX_URL_LENGTH),
+    ];
+
+    for (field, value, max_length) in fields.iter() {
+        let value = match value {
+            Some(value) => value.trim(),
+            None => continue,
+        };
+        if value.chars().count() > *max_length {
+            errors.add(
+                field,
+                format!("is too long (maximum is {} characters)", max_length),
+            );
+        }
+    }
+
+    for (field, value) in &[("website", &payload.website), ("avatar_url", &payload.avatar_url)] {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+            if !is_web_url(value) {
+                errors.add(field, "must be an http or https URL");
+            }
+        }
+    }
+}
+
+/// Expects the keyword to already be trimmed.
+pub fn validate_muted_keyword(keyword: &str, errors: &mut FieldErrors) {
+    if keyword.is_empty() {
+        errors.add("keyword", "can't be blank");
+    } else if keyword.chars().count() > MAX_MUTED_KEYWORD_LENGTH {
+        errors.add(
+            "keyword",
+            format!("is too long (maximum is {} characters)", MAX_MUTED_KEYWORD_LENGTH),
+        );
+    }
+}
+
+pub fn validate_expires_at(
+    expires_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    errors: &mut FieldErrors,
+) {
+    if let Some(expires_at) = expires_at {
+        if expires_at <= now {
+            errors.add("expires_at", "must be in the future");
+        }
+    }
+}
+
+fn is_web_url(value: &str) -> bool {
+    match Url::parse(value) {
+        Ok(url) => (url.scheme() == "http" || url.scheme() == "https") && url.host().is_some(),
+        Err(_) => false,
+    }
+}
+
+fn humanize(field: &str) -> String {
+    let field = field.replace('_', " ");
+    let mut chars = field.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -1,18 +1,23 @@
 use crate::{Error, Model, Msg};
-use payloads::{LoginPayload, CreateTweetPayload};
+use payloads::{
+    ChangePasswordPayload, ConfirmPasswordResetPayload, CreateTweetPayload, LoginPayload,
+    MuteUserPayload,
+};
 use seed::{prelude::*, *};
+use shared::errors::ApiErrorResponse;
 use shared::payloads::CreateUserPayload;
 use shared::responses::{ApiResponse, TokenResponse, UserResponse};
 use shared::Url as _;
 use shared::*;
+use uuid::Uuid;
 
 const API_URL: &'static str = "http://localhost:8080";
 
-pub async fn create_user(username: String, password: String) -> Msg {
+pub async fn create_user(username: String, password: String, email: Option<String>) -> Msg {
     fetch::<CreateUser>(
         None, 
         CreateUserUrl, 
-        CreateUserPayload { username, password }, 
+        CreateUserPayload { username, password, email }, 
         |resp| Msg::CreateUserEndpointResponded(resp.token),
     ).await
 }
@@ -35,6 +40,16 @@ pub async fn reload_current_user(auth_token: String) -> Msg {
     ).await
 }
 
+pub async fn logout(auth_token: String, username: String) -> Msg {
+    fetch::<Logout>(
+        Some(auth_token),
+        LogoutUrl { username },
+        NoPayLoad,
+        |_| Msg::Noop,
+    )
+    .await
+}
+
 pub async fn load_user(username: String, auth_token: Option<String>) -> Msg {
     fetch::<GetUser>(
         auth_token,
@@ -57,16 +72,98 @@ pub async fn load_timeline(auth_token: Option<String>) -> Msg {
     .await
 }
 
+pub async fn load_tweet(auth_token: Option<String>, id: Uuid) -> Msg {
+    fetch::<GetTweet>(auth_token, TweetUrl { id }, NoPayLoad, Msg::TweetLoaded).await
+}
+
+pub async fn delete_tweet(auth_token: Option<String>, id: Uuid) -> Msg {
+    fetch::<DeleteTweet>(
+        auth_token,
+        TweetUrl { id },
+        NoPayLoad,
+        |_| Msg::DeleteTweetEndpointResponded,
+    )
+    .await
+}
+
+pub async fn retweet(auth_token: Option<String>, id: Uuid) -> Msg {
+    fetch::<Retweet>(
+        auth_token,
+        RetweetUrl { id },
+        NoPayLoad,
+        |_| Msg::RetweetEndpointResponded,
+    )
+    .await
+}
+
+pub async fn like(auth_token: Option<String>, id: Uuid) -> Msg {
+    fetch::<Like>(
+        auth_token,
+        LikeUrl { id },
+        NoPayLoad,
+        |_| Msg::Noop,
+    )
+    .await
+}
+
+pub async fn unlike(auth_token: Option<String>, id: Uuid) -> Msg {
+    fetch::<Unlike>(
+        auth_token,
+        LikeUrl { id },
+        NoPayLoad,
+        |_| Msg::Noop,
+    )
+    .await
+}
+
 pub async fn post_tweet(auth_token: Option<String>, text: String) -> Msg {
     fetch::<PostTweet>(
         auth_token,
         PostTweetUrl,
-        CreateTweetPayload { text },
+        CreateTweetPayload {
+            text,
+            in_reply_to_id: None,
+            quoted_tweet_id: None,
+        },
         Msg::PostTweetEndpointResponded,
     )
     .await
 }
 
+pub async fn mute_user(auth_token: Option<String>, username: String) -> Msg {
+    fetch::<MuteUser>(
+        auth_token,
+        MuteUserUrl { username },
+        MuteUserPayload::default(),
+        Msg::MuteUserEndpointResponded,
+    )
+    .await
+}
+
+pub async fn change_password(
+    auth_token: Option<String>,
+    current_password: String,
+    new_password: String,
+) -> Msg {
+    fetch::<ChangePassword>(
+        auth_token,
+        ChangePasswordUrl,
+        ChangePasswordPayload { current_password, new_password },
+        |_| Msg::ChangePasswordEndpointResponded,
+    )
+    .await
+}
+
+pub async fn confirm_password_reset(token: String, new_password: String) -> Msg {
+    fetch::<ConfirmPasswordReset>(
+        None,
+        ConfirmPasswordResetUrl,
+        ConfirmPasswordResetPayload { token, new_password },
+        |_| Msg::ResetPasswordEndpointResponded,
+    )
+    .await
+}
+
 pub async fn fetch<E>(
     auth_token: Option<String>, 
     url: E::Url,
@@ -90,8 +187,14 @@ where
 
         let resp = seed::browser::fetch::fetch(req).await?;
 
+        // Failed requests come back with a body explaining why, hand that
+        // over instead of just the status
+        if !resp.status().is_ok() {
+            let api_error = resp.json::<ApiErrorResponse>().await?.error;
+            return seed::browser::fetch::Result::Ok(Msg::Error(Error::Api(api_error)));
+        }
+
         let value = resp
-            .check_status()? // ensure we've got 2xx status
             .json::<ApiResponse<E::Response>>()
             .await?
             .data;
@@ -152,3 +255,6 @@ macro_rules! impl_set_request_payload {
 impl_set_request_payload!(CreateTweetPayload);
 impl_set_request_payload!(LoginPayload);
 impl_set_request_payload!(CreateUserPayload);
+impl_set_request_payload!(ChangePasswordPayload);
+impl_set_request_payload!(ConfirmPasswordResetPayload);
+impl_set_request_payload!(MuteUserPayload);
@@ -1,7 +1,9 @@
 use flash::Flash;
 use seed::{prelude::*, *};
-use shared::responses::{UserResponse, TweetResponse, PostTweetResponse};
+use shared::errors::{ApiError, ErrorCode};
+use shared::responses::{MutedUserResponse, UserResponse, TweetResponse, PostTweetResponse};
 use std::fmt;
+use uuid::Uuid;
 use web_sys::HtmlInputElement;
 
 
@@ -20,6 +22,8 @@ pub struct Model {
     login_form: LoginForm,
     sign_up_form: SignUpForm,
     post_tweet_form: PostTweetForm,
+    change_password_form: ChangePasswordForm,
+    reset_password_form: ResetPasswordForm,
     auth_token: Option<String>,
     current_user: Option<UserResponse>,
     page: Page,
@@ -52,6 +56,7 @@ pub struct LoginForm {
 pub struct SignUpForm {
     username_input: ElRef<HtmlInputElement>,
     password_input: ElRef<HtmlInputElement>,
+    email_input: ElRef<HtmlInputElement>,
 }
 
 #[derive(Debug, Default)]
@@ -59,6 +64,17 @@ pub struct PostTweetForm {
     text_input: ElRef<HtmlInputElement>,
 }
 
+#[derive(Debug, Default)]
+pub struct ChangePasswordForm {
+    current_password_input: ElRef<HtmlInputElement>,
+    new_password_input: ElRef<HtmlInputElement>,
+}
+
+#[derive(Debug, Default)]
+pub struct ResetPasswordForm {
+    new_password_input: ElRef<HtmlInputElement>,
+}
+
 #[derive(Debug)]
 pub enum PageData<T> {
     Loaded(T),
@@ -71,9 +87,14 @@ enum Page {
     Timeline(PageData<Vec<TweetResponse>>),
     Login,
     SignUp,
-    UserProfile(String),
+    UserProfile(String, PageData<UserResponse>),
     SignedIn,
     PostTweet,
+    Tweet(Uuid, PageData<TweetResponse>),
+    ChangePassword,
+    /// Where password reset emails link to, with the token from the email.
+    ResetPassword(String),
+    NotFound(String),
 }
 
 impl Page {
@@ -90,13 +111,23 @@ impl Page {
 
     fn load_data(&self, orders: &mut impl Orders<Msg>) {
         match self {
-            Page::UserProfile(username) => {
+            Page::UserProfile(username, _) => {
                 orders.send_msg(Msg::LoadUserProfile(username.to_string()));
                 }
             Page::Timeline(_) => {
                 orders.send_msg(Msg::LoadTimeline);
                 }
-            Page::RootLoggedOut | Page::Login | Page::SignUp | Page::SignedIn | Page::PostTweet => {}
+            Page::Tweet(id, _) => {
+                orders.send_msg(Msg::LoadTweet(*id));
+            }
+            Page::RootLoggedOut
+            | Page::Login
+            | Page::SignUp
+            | Page::SignedIn
+            | Page::PostTweet
+            | Page::ChangePassword
+            | Page::ResetPassword(_)
+            | Page::NotFound(_) => {}
         }
     }
 
@@ -106,7 +137,7 @@ impl Page {
         match path.as_slice() {
             ["sign_up"] => Page::SignUp,
             ["login"] => Page::Login,
-            ["users", username] => Page::UserProfile(username.to_string()),
+            ["users", username] => Page::UserProfile(username.to_string(), PageData::NotLoaded),
             [] => if model.logged_in() {
                 Page::Timeline(PageData::NotLoaded)
             } else {
@@ -114,7 +145,19 @@ impl Page {
             },
             ["signed_in"] => Page::SignedIn,
             ["tweets", "new"] => Page::PostTweet,
-            _ => todo!("Unknown URL: {}", url),
+            ["tweets", id] => match id.parse::<Uuid>() {
+                Ok(id) => Page::Tweet(id, PageData::NotLoaded),
+                Err(_) => Page::NotFound(url.to_string()),
+            },
+            ["settings", "password"] => Page::ChangePassword,
+            ["reset_password"] => Page::ResetPassword(
+                url.search()
+                    .get("token")
+                    .and_then(|values| values.first())
+                    .cloned()
+                    .unwrap_or_default(),
+            ),
+            _ => Page::NotFound(url.to_string()),
         }
     }
 
@@ -127,9 +170,13 @@ impl fmt::Display for Page {
             Page::Timeline(_) => write!(f, "/"),
             Page::Login => write!(f, "/login"), 
             Page::SignUp => write!(f, "/sign_up"),
-            Page::UserProfile(username) => write!(f, "/users/{}", username.clone()),
+            Page::UserProfile(username, _) => write!(f, "/users/{}", username.clone()),
             Page::SignedIn => write!(f, "/signed_in"),
             Page::PostTweet => write!(f, "/tweets/new"),
+            Page::Tweet(id, _) => write!(f, "/tweets/{}", id),
+            Page::ChangePassword => write!(f, "/settings/password"),
+            Page::ResetPassword(token) => write!(f, "/reset_password?token={}", token),
+            Page::NotFound(url) => write!(f, "{}", url),
         }
     }
 }
@@ -166,13 +213,27 @@ pub enum Msg {
     LoadTimeline,
     PostTweetFormSubmitted,
     PostTweetEndpointResponded(PostTweetResponse),
-    #[allow(dead_code)]
+    ChangePasswordFormSubmitted,
+    ChangePasswordEndpointResponded,
+    ResetPasswordFormSubmitted,
+    ResetPasswordEndpointResponded,
+    LoadTweet(Uuid),
+    TweetLoaded(TweetResponse),
+    DeleteTweet(Uuid),
+    DeleteTweetEndpointResponded,
+    Retweet(Uuid),
+    RetweetEndpointResponded,
+    Like(Uuid),
+    Unlike(Uuid),
+    MuteUser(String),
+    MuteUserEndpointResponded(MutedUserResponse),
     Noop,
 }
 
 #[derive(Debug)]
 pub enum Error {
     RequestFailed(FetchError),
+    Api(ApiError),
 }
 
 fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
@@ -204,7 +265,9 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             let form = &model.sign_up_form;
             let username = form.username_input.get().unwrap().value();
             let password = form.password_input.get().unwrap().value();
-            orders.perform_cmd(api::create_user(username, password));
+            let email = Some(form.email_input.get().unwrap().value())
+                .filter(|email| !email.is_empty());
+            orders.perform_cmd(api::create_user(username, password, email));
         }
         Msg::CreateUserEndpointResponded(token) => {
             model.set_auth_token(&token);
@@ -215,8 +278,22 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             orders.perform_cmd(api::load_user(username, model.auth_token.clone()));
 
         }
-        Msg::GetUserLoaded(user) => log!("user loaded", user),
+        Msg::GetUserLoaded(user) => {
+            if let Page::UserProfile(username, data) = &mut model.page {
+                if *username == user.username {
+                    *data = PageData::Loaded(user);
+                }
+            }
+        }
         Msg::TweetPosted(tweet) => log!(tweet),
+        Msg::Error(Error::Api(err)) => {
+            log!("request failed", err);
+
+            if err.code == ErrorCode::InvalidToken {
+                model.remove_auth_token();
+            }
+            model.flash.set_error(&err.message, orders);
+        }
         Msg::Error(err) => {
             log!("request failed", err);
 
@@ -227,6 +304,11 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             model.flash.clear();
         }
         Msg::Logout => {
+            // Forget the token right away, the server ending the session can
+            // finish in the background
+            if let (Some(token), Some(user)) = (&model.auth_token, &model.current_user) {
+                orders.perform_cmd(api::logout(token.clone(), user.username.clone()));
+            }
             Page::RootLoggedOut.go(model, orders);
             model.remove_auth_token();
         }
@@ -247,6 +329,96 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             model.flash.set_notice("Tweet posted", orders);
             Page::Timeline(PageData::NotLoaded).go(model, orders);
         }
+        Msg::ChangePasswordFormSubmitted => {
+            let form = &model.change_password_form;
+            let current_password = form.current_password_input.get().unwrap().value();
+            let new_password = form.new_password_input.get().unwrap().value();
+            orders.perform_cmd(api::change_password(
+                model.auth_token.clone(),
+                current_password,
+                new_password,
+            ));
+        }
+        Msg::ChangePasswordEndpointResponded => {
+            model.flash.set_notice("Password changed", orders);
+            Page::Timeline(PageData::NotLoaded).go(model, orders);
+        }
+        Msg::ResetPasswordFormSubmitted => {
+            if let Page::ResetPassword(token) = &model.page {
+                let new_password = model
+                    .reset_password_form
+                    .new_password_input
+                    .get()
+                    .unwrap()
+                    .value();
+                orders.perform_cmd(api::confirm_password_reset(token.clone(), new_password));
+            }
+        }
+        Msg::ResetPasswordEndpointResponded => {
+            model
+                .flash
+                .set_notice("Password changed, log in with the new one", orders);
+            Page::Login.go(model, orders);
+        }
+        Msg::LoadTweet(id) => {
+            orders.perform_cmd(api::load_tweet(model.auth_token.clone(), id));
+        }
+        Msg::TweetLoaded(tweet) => {
+            if let Page::Tweet(id, data) = &mut model.page {
+                if *id == tweet.id {
+                    *data = PageData::Loaded(tweet);
+                }
+            }
+        }
+        Msg::DeleteTweet(id) => {
+            orders.perform_cmd(api::delete_tweet(model.auth_token.clone(), id));
+        }
+        Msg::DeleteTweetEndpointResponded => {
+            model.flash.set_notice("Tweet deleted", orders);
+            Page::Timeline(PageData::NotLoaded).go(model, orders);
+        }
+        Msg::Retweet(id) => {
+            orders.perform_cmd(api::retweet(model.auth_token.clone(), id));
+        }
+        Msg::RetweetEndpointResponded => {
+            model.flash.set_notice("Retweeted", orders);
+        }
+        Msg::Like(id) => {
+            set_liked(model, id, true);
+            orders.perform_cmd(api::like(model.auth_token.clone(), id));
+        }
+        Msg::Unlike(id) => {
+            set_liked(model, id, false);
+            orders.perform_cmd(api::unlike(model.auth_token.clone(), id));
+        }
+        Msg::MuteUser(username) => {
+            orders.perform_cmd(api::mute_user(model.auth_token.clone(), username));
+        }
+        Msg::MuteUserEndpointResponded(mute) => {
+            // The server already leaves them out, no need to reload
+            if let Page::Timeline(PageData::Loaded(tweets)) = &mut model.page {
+                tweets.retain(|tweet| tweet.user.id != mute.user.id);
+            }
+            model
+                .flash
+                .set_notice(&format!("Muted @{}", mute.user.username), orders);
+        }
+    }
+}
+
+/// Updates the tweet on screen straight away instead of reloading it after
+/// the request.
+fn set_liked(model: &mut Model, id: Uuid, liked: bool) {
+    let tweets = match &mut model.page {
+        Page::Timeline(PageData::Loaded(tweets)) => tweets.iter_mut().collect::<Vec<_>>(),
+        Page::Tweet(_, PageData::Loaded(tweet)) => vec![tweet],
+        _ => vec![],
+    };
+    for tweet in tweets {
+        if tweet.id == id && tweet.liked_by_me != Some(liked) {
+            tweet.like_count += if liked { 1 } else { -1 };
+            tweet.liked_by_me = Some(liked);
+        }
     }
 }
 
@@ -272,6 +444,8 @@ fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
         login_form: Default::default(),
         sign_up_form: Default::default(),
         post_tweet_form: Default::default(),
+        change_password_form: Default::default(),
+        reset_password_form: Default::default(),
         flash: Default::default(),
     };
 
@@ -1,6 +1,6 @@
 use crate::{flash::FlashMsg, Model, Msg, Page, PageData};
 use seed::{prelude::*, *};
-use shared::responses::TweetResponse;
+use shared::responses::{TweetResponse, UserResponse};
 
 // `view` describes what to display, based on the state of the model
 pub fn view(model: &Model) -> Vec<Node<Msg>> {
@@ -17,9 +17,13 @@ fn view_page(model: &Model) -> Node<Msg> {
         Page::Timeline(tweets) => timeline(model, tweets),
         Page::Login => login(model),
         Page::SignUp => sign_up(model),
-        Page::UserProfile(username) => user_profile(username),
+        Page::UserProfile(_, user) => user_profile(user),
         Page::SignedIn => signed_in(),
         Page::PostTweet => post_tweet(model),
+        Page::Tweet(_, tweet) => tweet_page(model, tweet),
+        Page::ChangePassword => change_password(model),
+        Page::ResetPassword(_) => reset_password(model),
+        Page::NotFound(_) => p!["Page not found"],
     }
 }
 
@@ -27,7 +31,10 @@ fn timeline(model: &Model, tweets: &PageData<Vec<TweetResponse>>) -> Node<Msg> {
     match tweets {
         PageData::NotLoaded => p!["Loading..."],
         PageData::Loaded(tweets) => {
-            let tweets_views: Vec<Node<Msg>> = tweets.iter().map(tweet).collect::<Vec<_>>();
+            let tweets_views: Vec<Node<Msg>> = tweets
+                .iter()
+                .map(|t| tweet(model, t))
+                .collect::<Vec<_>>();
             div![
                 tweets_views
             ]
@@ -35,22 +42,112 @@ fn timeline(model: &Model, tweets: &PageData<Vec<TweetResponse>>) -> Node<Msg> {
     }
 }
 
-fn tweet(tweet: &TweetResponse) -> Node<Msg> {
+fn tweet(model: &Model, tweet: &TweetResponse) -> Node<Msg> {
+    let can_mute = model
+        .current_user
+        .as_ref()
+        .map_or(false, |current_user| current_user.id != tweet.user.id);
+    // Protected tweets can only be retweeted by their author, who'd rather
+    // see the tweet itself
+    let can_retweet = can_mute && !tweet.user.protected;
+    let username = tweet.user.username.clone();
+    let id = tweet.id;
+
     div![
+        tweet.retweeted_by.as_ref().map(|retweeter| {
+            div![
+                "Retweeted by @",
+                &retweeter.username,
+            ]
+        }),
         a![
             "@", &tweet.user.username,
             attrs! {
-                At::Href => Page::UserProfile(tweet.user.username.to_string())
+                At::Href => Page::UserProfile(tweet.user.username.to_string(), PageData::NotLoaded)
             }
         ],
+        tweet.in_reply_to_id.map(|id| {
+            span![
+                " in reply to ",
+                a!["this tweet", attrs! { At::Href => Page::Tweet(id, PageData::NotLoaded) }],
+            ]
+        }),
         br![],
         &tweet.text,
+        tweet.quoted_tweet.as_ref().map(|quoted| {
+            blockquote![
+                a![
+                    "@", &quoted.user.username,
+                    attrs! { At::Href => Page::Tweet(quoted.id, PageData::NotLoaded) }
+                ],
+                br![],
+                &quoted.text,
+            ]
+        }),
         br![],
-        format!("{:?}", &tweet.created_at),
+        a![
+            format!("{:?}", &tweet.created_at),
+            attrs! { At::Href => Page::Tweet(tweet.id, PageData::NotLoaded) }
+        ],
+        tweet.edited_at.map(|_| span![" (edited)"]),
+        format!(" {} replies", tweet.reply_count),
+        format!(" {} retweets", tweet.retweet_count),
+        format!(" {} likes", tweet.like_count),
+        match tweet.liked_by_me {
+            Some(false) => Some(button![
+                "Like",
+                ev(Ev::Click, move |_| Msg::Like(id)),
+            ]),
+            Some(true) => Some(button![
+                "Unlike",
+                ev(Ev::Click, move |_| Msg::Unlike(id)),
+            ]),
+            None => None,
+        },
+        if can_retweet {
+            Some(button![
+                "Retweet",
+                ev(Ev::Click, move |_| Msg::Retweet(id)),
+            ])
+        } else {
+            None
+        },
+        if can_mute {
+            Some(button![
+                "Mute",
+                ev(Ev::Click, move |_| Msg::MuteUser(username)),
+            ])
+        } else {
+            None
+        },
         hr![],
     ]
 }
 
+fn tweet_page(model: &Model, data: &PageData<TweetResponse>) -> Node<Msg> {
+    let t = match data {
+        PageData::NotLoaded => return p!["Loading..."],
+        PageData::Loaded(t) => t,
+    };
+    let is_author = model
+        .current_user
+        .as_ref()
+        .map_or(false, |current_user| current_user.id == t.user.id);
+    let id = t.id;
+
+    div![
+        tweet(model, t),
+        if is_author {
+            Some(button![
+                "Delete",
+                ev(Ev::Click, move |_| Msg::DeleteTweet(id)),
+            ])
+        } else {
+            None
+        },
+    ]
+}
+
 fn post_tweet(model: &Model) -> Node<Msg> {
     div![
         div![input![
@@ -91,9 +188,13 @@ fn nav(model: &Model) -> Node<Msg> {
             " | ",
             a![
                 &current_user.username,
-                attrs! { At::Href => Page::UserProfile(current_user.username.clone()) }
+                attrs! {
+                    At::Href => Page::UserProfile(current_user.username.clone(), PageData::NotLoaded)
+                }
             ],
             " | ",
+            a!["Change password", attrs! { At::Href => Page::ChangePassword }],
+            " | ",
             a!["Logout", ev(Ev::Click, |_| Msg::Logout), attrs! { At::Href => "#" }],
         ]
     } else {
@@ -147,6 +248,13 @@ fn sign_up(model: &Model) -> Node<Msg> {
                 At::Placeholder => "Password"
             },
         ]],
+    div![input![
+            el_ref(&model.sign_up_form.email_input),
+            attrs! {
+                At::Type => "email",
+                At::Placeholder => "Email (optional)"
+            },
+        ]],
         div![button![
                 "Sign Up",
                 ev(Ev::Click, |_| Msg::SignUpFormSubmitted),
@@ -154,6 +262,85 @@ fn sign_up(model: &Model) -> Node<Msg> {
     ]
 }
 
-fn user_profile(username: &str) -> Node<Msg> {
-    p!["Profile of ", username]
-}
\ No newline at end of file
+fn user_profile(user: &PageData<UserResponse>) -> Node<Msg> {
+    let user = match user {
+        PageData::NotLoaded => return p!["Loading..."],
+        PageData::Loaded(user) => user,
+    };
+
+    div![
+        user.header_url.as_ref().map(|header_url| img![
+            attrs! {
+                At::Src => header_url,
+                At::Alt => format!("Header of @{}", user.username),
+                At::Width => 600,
+            }
+        ]),
+        user.avatar_url.as_ref().map(|avatar_url| img![
+            attrs! {
+                At::Src => avatar_url,
+                At::Alt => format!("Avatar of @{}", user.username),
+                At::Width => 96,
+            }
+        ]),
+        h2![user.display_name.as_deref().unwrap_or(&user.username)],
+        p!["@", &user.username],
+        p![format!(
+            "{} tweets · {} following · {} followers",
+            user.tweets_count, user.following_count, user.followers_count
+        )],
+        if user.follows_me == Some(true) {
+            Some(p!["Follows you"])
+        } else {
+            None
+        },
+        user.bio.as_ref().map(|bio| p![bio]),
+        user.location.as_ref().map(|location| p![location]),
+        user.website.as_ref().map(|website| p![a![
+            website,
+            attrs! {
+                At::Href => website,
+                At::Rel => "nofollow noopener",
+            }
+        ]]),
+    ]
+}
+
+fn change_password(model: &Model) -> Node<Msg> {
+    div![
+        div![input![
+            el_ref(&model.change_password_form.current_password_input),
+            attrs! {
+                At::Type => "password",
+                At::Placeholder => "Current password",
+            },
+        ]],
+        div![input![
+            el_ref(&model.change_password_form.new_password_input),
+            attrs! {
+                At::Type => "password",
+                At::Placeholder => "New password",
+            },
+        ]],
+        div![button![
+                "Change password",
+                ev(Ev::Click, |_| Msg::ChangePasswordFormSubmitted),
+        ]]
+    ]
+}
+
+fn reset_password(model: &Model) -> Node<Msg> {
+    div![
+        div![input![
+            el_ref(&model.reset_password_form.new_password_input),
+            attrs! {
+                At::Type => "password",
+                At::Placeholder => "New password",
+            },
+        ]],
+        div![button![
+            "Reset password",
+            ev(Ev::Click, |_| Msg::ResetPasswordFormSubmitted),
+        ]]
+    ]
+}
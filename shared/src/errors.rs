@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Stable, machine readable reason for a failed request. Clients should
+/// branch on this rather than on `message`, which is meant for people and may
+/// change.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    InvalidPayload,
+    ValidationFailed,
+    MissingAuthToken,
+    InvalidAuthHeader,
+    InvalidToken,
+    InvalidCredentials,
+    IncorrectPassword,
+    InvalidResetToken,
+    Forbidden,
+    NotFound,
+    UserNotFound,
+    SessionNotFound,
+    TweetNotFound,
+    FollowRequestNotFound,
+    MuteNotFound,
+    UsernameTaken,
+    EmailTaken,
+    AccountUnavailable,
+    TweetTooLong,
+    EditWindowClosed,
+    ImageTooLarge,
+    UnsupportedImage,
+    CannotFollowSelf,
+    CannotBlockSelf,
+    UserBlocked,
+    CannotMuteSelf,
+    ProtectedAccount,
+    CannotRetweetProtected,
+    TooManyRequests,
+    AccountLocked,
+    InternalError,
+    /// A code this build doesn't know about yet.
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    /// The code used for errors that don't have a more specific one.
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            400 => ErrorCode::BadRequest,
+            401 => ErrorCode::InvalidToken,
+            403 => ErrorCode::Forbidden,
+            404 => ErrorCode::NotFound,
+            422 => ErrorCode::InvalidPayload,
+            429 => ErrorCode::TooManyRequests,
+            500..=599 => ErrorCode::InternalError,
+            _ => ErrorCode::Unknown,
+        }
+    }
+}
+
+/// Field name to the problems with it, e.g. `{"username": ["is reserved"]}`.
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+/// The body of every failed response, under an `error` key.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiError {
+    pub status_code: String,
+    pub code: ErrorCode,
+    pub message: String,
+    /// What was wrong with each field of an invalid payload. It sits in here
+    /// with the rest of the failure rather than next to `error`, so clients
+    /// only ever have the one error shape to handle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errors: Option<FieldErrors>,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiErrorResponse {
+    pub error: ApiError,
+}
@@ -1,10 +1,20 @@
 use http_types::Method;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+pub mod errors;
 pub mod payloads;
 pub mod responses;
 
 pub const MAX_TWEET_LENGTH: usize = 280;
+pub const MIN_USERNAME_LENGTH: usize = 3;
+pub const MAX_USERNAME_LENGTH: usize = 15;
+pub const MAX_DISPLAY_NAME_LENGTH: usize = 50;
+pub const MAX_BIO_LENGTH: usize = 160;
+pub const MAX_LOCATION_LENGTH: usize = 30;
+pub const MAX_URL_LENGTH: usize = 200;
+pub const MAX_MUTED_KEYWORD_LENGTH: usize = 100;
+/// Largest image file that can be uploaded, in bytes.
+pub const MAX_IMAGE_UPLOAD_SIZE: usize = 5 * 1024 * 1024;
 
 pub trait Url {
     const URL_SPEC: &'static str;
@@ -42,6 +52,28 @@ impl Url for GetUserUrl {
     }
 }
 
+/// Ends the session the request is authenticated with.
+pub struct Logout;
+
+impl ApiEndpoint for Logout {
+    type Url = LogoutUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct LogoutUrl {
+    pub username: String,
+}
+
+impl Url for LogoutUrl {
+    const URL_SPEC: &'static str = "/users/:username/session";
+
+    fn url(&self) -> String {
+        format!("/users/{}/session", self.username)
+    }
+}
+
 pub struct PostTweet;
 
 impl ApiEndpoint for PostTweet {
@@ -61,6 +93,159 @@ impl Url for PostTweetUrl {
     }
 }
 
+/// Protected accounts' tweets are only there for their followers, anyone
+/// else gets a 403.
+pub struct GetTweet;
+
+impl ApiEndpoint for GetTweet {
+    type Url = TweetUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = responses::TweetResponse;
+}
+
+/// Only the author can delete a tweet.
+pub struct DeleteTweet;
+
+impl ApiEndpoint for DeleteTweet {
+    type Url = TweetUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+/// Only the author can edit a tweet, and only for a while after posting it.
+/// The text it replaces is kept for `TweetHistory`.
+pub struct EditTweet;
+
+impl ApiEndpoint for EditTweet {
+    type Url = TweetUrl;
+    const METHOD: Method = Method::Patch;
+    type Payload = payloads::EditTweetPayload;
+    type Response = responses::TweetResponse;
+}
+
+pub struct TweetUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for TweetUrl {
+    const URL_SPEC: &'static str = "/tweets/:id";
+
+    fn url(&self) -> String {
+        format!("/tweets/{}", self.id)
+    }
+}
+
+/// Retweeting the same tweet again does nothing and answers 200 instead of
+/// 201. Protected tweets can't be retweeted.
+pub struct Retweet;
+
+impl ApiEndpoint for Retweet {
+    type Url = RetweetUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct Unretweet;
+
+impl ApiEndpoint for Unretweet {
+    type Url = RetweetUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct RetweetUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for RetweetUrl {
+    const URL_SPEC: &'static str = "/tweets/:id/retweet";
+
+    fn url(&self) -> String {
+        format!("/tweets/{}/retweet", self.id)
+    }
+}
+
+/// Liking the same tweet again does nothing and answers 200 instead of 201.
+pub struct Like;
+
+impl ApiEndpoint for Like {
+    type Url = LikeUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct Unlike;
+
+impl ApiEndpoint for Unlike {
+    type Url = LikeUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct LikeUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for LikeUrl {
+    const URL_SPEC: &'static str = "/tweets/:id/like";
+
+    fn url(&self) -> String {
+        format!("/tweets/{}/like", self.id)
+    }
+}
+
+/// A tweet with the tweets it replies to and a page of the replies to it.
+/// `?page=` and `?page_size=` page through the replies.
+pub struct Conversation;
+
+impl ApiEndpoint for Conversation {
+    type Url = ConversationUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = responses::ConversationResponse;
+}
+
+pub struct ConversationUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for ConversationUrl {
+    const URL_SPEC: &'static str = "/tweets/:id/conversation";
+
+    fn url(&self) -> String {
+        format!("/tweets/{}/conversation", self.id)
+    }
+}
+
+/// Earlier texts of an edited tweet, newest first. Visible to whoever can
+/// see the tweet.
+pub struct TweetHistory;
+
+impl ApiEndpoint for TweetHistory {
+    type Url = TweetHistoryUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::TweetRevisionResponse>;
+}
+
+pub struct TweetHistoryUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for TweetHistoryUrl {
+    const URL_SPEC: &'static str = "/tweets/:id/history";
+
+    fn url(&self) -> String {
+        format!("/tweets/{}/history", self.id)
+    }
+}
+
 pub struct Me;
 
 impl ApiEndpoint for Me {
@@ -80,6 +265,15 @@ impl Url for MeUrl {
     }
 }
 
+pub struct UpdateProfile;
+
+impl ApiEndpoint for UpdateProfile {
+    type Url = MeUrl;
+    const METHOD: Method = Method::Patch;
+    type Payload = payloads::UpdateProfilePayload;
+    type Response = responses::UserResponse;
+}
+
 pub struct Login;
 
 impl ApiEndpoint for Login {
@@ -138,3 +332,544 @@ impl Url for TimelineUrl {
         format!("/me/timeline")
     }
 }
+
+pub struct Sessions;
+
+impl ApiEndpoint for Sessions {
+    type Url = SessionsUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::SessionResponse>;
+}
+
+pub struct SessionsUrl;
+
+impl Url for SessionsUrl {
+    const URL_SPEC: &'static str = "/me/sessions";
+
+    fn url(&self) -> String {
+        format!("/me/sessions")
+    }
+}
+
+pub struct RevokeSession;
+
+impl ApiEndpoint for RevokeSession {
+    type Url = RevokeSessionUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct RevokeSessionUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for RevokeSessionUrl {
+    const URL_SPEC: &'static str = "/me/sessions/:id";
+
+    fn url(&self) -> String {
+        format!("/me/sessions/{}", self.id)
+    }
+}
+
+pub struct ChangePassword;
+
+impl ApiEndpoint for ChangePassword {
+    type Url = ChangePasswordUrl;
+    const METHOD: Method = Method::Put;
+    type Payload = payloads::ChangePasswordPayload;
+    type Response = ();
+}
+
+pub struct ChangePasswordUrl;
+
+impl Url for ChangePasswordUrl {
+    const URL_SPEC: &'static str = "/me/password";
+
+    fn url(&self) -> String {
+        format!("/me/password")
+    }
+}
+
+pub struct RequestPasswordReset;
+
+impl ApiEndpoint for RequestPasswordReset {
+    type Url = RequestPasswordResetUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = payloads::RequestPasswordResetPayload;
+    type Response = ();
+}
+
+pub struct RequestPasswordResetUrl;
+
+impl Url for RequestPasswordResetUrl {
+    const URL_SPEC: &'static str = "/password_resets";
+
+    fn url(&self) -> String {
+        format!("/password_resets")
+    }
+}
+
+pub struct ConfirmPasswordReset;
+
+impl ApiEndpoint for ConfirmPasswordReset {
+    type Url = ConfirmPasswordResetUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = payloads::ConfirmPasswordResetPayload;
+    type Response = ();
+}
+
+pub struct ConfirmPasswordResetUrl;
+
+impl Url for ConfirmPasswordResetUrl {
+    const URL_SPEC: &'static str = "/password_resets/confirm";
+
+    fn url(&self) -> String {
+        format!("/password_resets/confirm")
+    }
+}
+
+pub struct DeleteAccount;
+
+impl ApiEndpoint for DeleteAccount {
+    type Url = DeleteAccountUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = payloads::DeleteAccountPayload;
+    type Response = responses::AccountDeletionResponse;
+}
+
+pub struct DeleteAccountUrl;
+
+impl Url for DeleteAccountUrl {
+    const URL_SPEC: &'static str = "/me";
+
+    fn url(&self) -> String {
+        format!("/me")
+    }
+}
+
+/// Served as a download rather than wrapped in `data`, so it isn't
+/// registered with `add_endpoint`.
+pub struct ExportData;
+
+impl ApiEndpoint for ExportData {
+    type Url = ExportDataUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = responses::ExportArchive;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Zip,
+}
+
+impl Default for ExportFormat {
+    fn default() -> Self {
+        ExportFormat::Json
+    }
+}
+
+pub struct ExportDataUrl {
+    pub format: ExportFormat,
+}
+
+impl Url for ExportDataUrl {
+    const URL_SPEC: &'static str = "/me/export";
+
+    fn url(&self) -> String {
+        match self.format {
+            ExportFormat::Json => format!("/me/export"),
+            ExportFormat::Zip => format!("/me/export?format=zip"),
+        }
+    }
+}
+
+/// An uploaded file. The response is the file itself rather than JSON, so
+/// this is only here for its URL; `UserResponse` already carries full URLs
+/// to these.
+pub struct GetBlob;
+
+impl ApiEndpoint for GetBlob {
+    type Url = BlobUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<u8>;
+}
+
+pub struct BlobUrl {
+    pub key: String,
+}
+
+impl Url for BlobUrl {
+    const URL_SPEC: &'static str = "/blobs/*key";
+
+    fn url(&self) -> String {
+        format!("/blobs/{}", self.key)
+    }
+}
+
+/// The body is `multipart/form-data` with the file in an `image` part.
+pub struct UploadAvatar;
+
+impl ApiEndpoint for UploadAvatar {
+    type Url = UploadAvatarUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = NoPayLoad;
+    type Response = responses::UserResponse;
+}
+
+pub struct UploadAvatarUrl;
+
+impl Url for UploadAvatarUrl {
+    const URL_SPEC: &'static str = "/me/avatar";
+
+    fn url(&self) -> String {
+        format!("/me/avatar")
+    }
+}
+
+/// The body is `multipart/form-data` with the file in an `image` part.
+pub struct UploadHeader;
+
+impl ApiEndpoint for UploadHeader {
+    type Url = UploadHeaderUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = NoPayLoad;
+    type Response = responses::UserResponse;
+}
+
+pub struct UploadHeaderUrl;
+
+impl Url for UploadHeaderUrl {
+    const URL_SPEC: &'static str = "/me/header";
+
+    fn url(&self) -> String {
+        format!("/me/header")
+    }
+}
+
+/// Responds with 201 once following, or 202 if the account is protected and
+/// a follow request was sent instead.
+pub struct Follow;
+
+impl ApiEndpoint for Follow {
+    type Url = FollowUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+/// Also withdraws a pending follow request.
+pub struct Unfollow;
+
+impl ApiEndpoint for Unfollow {
+    type Url = FollowUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct FollowUrl {
+    pub username: String,
+}
+
+impl Url for FollowUrl {
+    const URL_SPEC: &'static str = "/users/:username/follow";
+
+    fn url(&self) -> String {
+        format!("/users/{}/follow", self.username)
+    }
+}
+
+/// Also ends any follows and follow requests between the two accounts, in
+/// both directions. Responds with 201, or 200 if already blocked.
+pub struct Block;
+
+impl ApiEndpoint for Block {
+    type Url = BlockUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct Unblock;
+
+impl ApiEndpoint for Unblock {
+    type Url = BlockUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct BlockUrl {
+    pub username: String,
+}
+
+impl Url for BlockUrl {
+    const URL_SPEC: &'static str = "/users/:username/block";
+
+    fn url(&self) -> String {
+        format!("/users/{}/block", self.username)
+    }
+}
+
+/// Hides the user's tweets from the timeline without unfollowing them.
+/// Responds with 201, or 200 if they were already muted.
+pub struct MuteUser;
+
+impl ApiEndpoint for MuteUser {
+    type Url = MuteUserUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = payloads::MuteUserPayload;
+    type Response = responses::MutedUserResponse;
+}
+
+pub struct UnmuteUser;
+
+impl ApiEndpoint for UnmuteUser {
+    type Url = MuteUserUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct MuteUserUrl {
+    pub username: String,
+}
+
+impl Url for MuteUserUrl {
+    const URL_SPEC: &'static str = "/users/:username/mute";
+
+    fn url(&self) -> String {
+        format!("/users/{}/mute", self.username)
+    }
+}
+
+/// Mutes that haven't expired yet.
+pub struct MutedUsers;
+
+impl ApiEndpoint for MutedUsers {
+    type Url = MutedUsersUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::MutedUserResponse>;
+}
+
+pub struct MutedUsersUrl;
+
+impl Url for MutedUsersUrl {
+    const URL_SPEC: &'static str = "/me/mutes/users";
+
+    fn url(&self) -> String {
+        format!("/me/mutes/users")
+    }
+}
+
+/// Responds with 201, or 200 if the keyword was already muted.
+pub struct MuteKeyword;
+
+impl ApiEndpoint for MuteKeyword {
+    type Url = MutedKeywordsUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = payloads::MuteKeywordPayload;
+    type Response = responses::MutedKeywordResponse;
+}
+
+/// Mutes that haven't expired yet.
+pub struct MutedKeywords;
+
+impl ApiEndpoint for MutedKeywords {
+    type Url = MutedKeywordsUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::MutedKeywordResponse>;
+}
+
+pub struct MutedKeywordsUrl;
+
+impl Url for MutedKeywordsUrl {
+    const URL_SPEC: &'static str = "/me/mutes/keywords";
+
+    fn url(&self) -> String {
+        format!("/me/mutes/keywords")
+    }
+}
+
+pub struct UnmuteKeyword;
+
+impl ApiEndpoint for UnmuteKeyword {
+    type Url = UnmuteKeywordUrl;
+    const METHOD: Method = Method::Delete;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct UnmuteKeywordUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for UnmuteKeywordUrl {
+    const URL_SPEC: &'static str = "/me/mutes/keywords/:id";
+
+    fn url(&self) -> String {
+        format!("/me/mutes/keywords/{}", self.id)
+    }
+}
+
+/// Who the user follows, newest first. Takes `?page=` and `?page_size=`
+/// like `Timeline`.
+pub struct Following;
+
+impl ApiEndpoint for Following {
+    type Url = FollowingUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::UserResponse>;
+}
+
+pub struct FollowingUrl {
+    pub username: String,
+}
+
+impl Url for FollowingUrl {
+    const URL_SPEC: &'static str = "/users/:username/following";
+
+    fn url(&self) -> String {
+        format!("/users/{}/following", self.username)
+    }
+}
+
+/// Who follows the user, newest first. Takes `?page=` and `?page_size=`
+/// like `Timeline`.
+pub struct Followers;
+
+impl ApiEndpoint for Followers {
+    type Url = FollowersUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::UserResponse>;
+}
+
+pub struct FollowersUrl {
+    pub username: String,
+}
+
+impl Url for FollowersUrl {
+    const URL_SPEC: &'static str = "/users/:username/followers";
+
+    fn url(&self) -> String {
+        format!("/users/{}/followers", self.username)
+    }
+}
+
+pub struct FollowRequests;
+
+impl ApiEndpoint for FollowRequests {
+    type Url = FollowRequestsUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::FollowRequestResponse>;
+}
+
+pub struct FollowRequestsUrl;
+
+impl Url for FollowRequestsUrl {
+    const URL_SPEC: &'static str = "/me/follow_requests";
+
+    fn url(&self) -> String {
+        format!("/me/follow_requests")
+    }
+}
+
+pub struct ApproveFollowRequest;
+
+impl ApiEndpoint for ApproveFollowRequest {
+    type Url = ApproveFollowRequestUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct ApproveFollowRequestUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for ApproveFollowRequestUrl {
+    const URL_SPEC: &'static str = "/me/follow_requests/:id/approve";
+
+    fn url(&self) -> String {
+        format!("/me/follow_requests/{}/approve", self.id)
+    }
+}
+
+pub struct RejectFollowRequest;
+
+impl ApiEndpoint for RejectFollowRequest {
+    type Url = RejectFollowRequestUrl;
+    const METHOD: Method = Method::Post;
+    type Payload = NoPayLoad;
+    type Response = ();
+}
+
+pub struct RejectFollowRequestUrl {
+    pub id: uuid::Uuid,
+}
+
+impl Url for RejectFollowRequestUrl {
+    const URL_SPEC: &'static str = "/me/follow_requests/:id/reject";
+
+    fn url(&self) -> String {
+        format!("/me/follow_requests/{}/reject", self.id)
+    }
+}
+
+/// Newest first, paginated like `Timeline`. Protected accounts only list
+/// their tweets for themselves and their followers.
+pub struct UserTweets;
+
+impl ApiEndpoint for UserTweets {
+    type Url = UserTweetsUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::TweetResponse>;
+}
+
+pub struct UserTweetsUrl {
+    pub username: String,
+}
+
+impl Url for UserTweetsUrl {
+    const URL_SPEC: &'static str = "/users/:username/tweets";
+
+    fn url(&self) -> String {
+        format!("/users/{}/tweets", self.username)
+    }
+}
+
+/// The tweets a user has liked, most recently liked first. Paginated with
+/// `?page=` and `?page_size=`. Like their tweets, a protected account's likes
+/// are only there for its followers.
+pub struct UserLikes;
+
+impl ApiEndpoint for UserLikes {
+    type Url = UserLikesUrl;
+    const METHOD: Method = Method::Get;
+    type Payload = NoPayLoad;
+    type Response = Vec<responses::TweetResponse>;
+}
+
+pub struct UserLikesUrl {
+    pub username: String,
+}
+
+impl Url for UserLikesUrl {
+    const URL_SPEC: &'static str = "/users/:username/likes";
+
+    fn url(&self) -> String {
+        format!("/users/{}/likes", self.username)
+    }
+}
@@ -1,9 +1,14 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateUserPayload {
     pub username: String,
     pub password: String,
+    /// Only needed to be able to reset a forgotten password.
+    #[serde(default)]
+    pub email: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -11,8 +16,74 @@ pub struct LoginPayload {
     pub password: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct CreateTweetPayload {
     pub text: String,
+    /// Makes the tweet a reply to this one, in the same conversation.
+    #[serde(default)]
+    pub in_reply_to_id: Option<Uuid>,
+    /// Makes the tweet a quote tweet, embedding this one below its text.
+    #[serde(default)]
+    pub quoted_tweet_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EditTweetPayload {
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ChangePasswordPayload {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RequestPasswordResetPayload {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfirmPasswordResetPayload {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DeleteAccountPayload {
+    pub password: String,
 }
 
+/// Fields that are left out stay as they are, an empty string clears one.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct UpdateProfilePayload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bio: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    /// Making an account public again approves every pending follow request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protected: Option<bool>,
+}
+
+/// Mutes without an expiry last until they're removed. Muting again replaces
+/// the expiry.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MuteUserPayload {
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Tweets containing `keyword` anywhere, ignoring case, are hidden.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MuteKeywordPayload {
+    pub keyword: String,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
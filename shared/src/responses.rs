@@ -15,6 +15,7 @@ impl<T> ApiResponse<T> {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenResponse {
+    /// The bearer token to send in the `Authorization` header.
     pub token: String,
 }
 
@@ -30,6 +31,38 @@ impl TokenResponse {
 pub struct UserResponse {
     pub id: Uuid,
     pub username: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    /// A small square version of an uploaded avatar, for lists and tweets.
+    #[serde(default)]
+    pub avatar_thumbnail_url: Option<String>,
+    #[serde(default)]
+    pub header_url: Option<String>,
+    /// Only approved followers see this user's tweets.
+    #[serde(default)]
+    pub protected: bool,
+    #[serde(default)]
+    pub followers_count: i64,
+    #[serde(default)]
+    pub following_count: i64,
+    #[serde(default)]
+    pub tweets_count: i64,
+    /// Whether whoever made the request follows this user. Left out when the
+    /// request isn't authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub followed_by_me: Option<bool>,
+    /// Whether this user follows whoever made the request. Left out when the
+    /// request isn't authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follows_me: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,12 +70,113 @@ pub struct TweetResponse {
     pub id: Uuid,
     pub text: String,
     pub created_at: DateTime<Utc>,
+    /// When the text was last changed, if it ever was.
+    pub edited_at: Option<DateTime<Utc>>,
+    /// How many earlier texts `TweetHistory` has for this tweet.
+    pub revision_count: i64,
+    /// The tweet this one replies to. `None` for the first tweet of a
+    /// conversation, or when the tweet replied to has been deleted.
+    pub in_reply_to_id: Option<Uuid>,
+    /// The id of the tweet that started the conversation.
+    pub conversation_id: Uuid,
+    /// Direct replies only, not replies to those.
+    pub reply_count: i64,
+    pub retweet_count: i64,
+    pub like_count: i64,
+    /// Whether whoever made the request likes this tweet. Left out when the
+    /// request isn't authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liked_by_me: Option<bool>,
+    /// The tweet this one quotes. `None` for plain tweets, or when the quoted
+    /// tweet has been deleted.
+    pub quoted_tweet_id: Option<Uuid>,
+    /// The tweet `quoted_tweet_id` points at, unless its author is hidden
+    /// from the viewer. Only one level deep, and left out of exports.
+    pub quoted_tweet: Option<Box<TweetResponse>>,
+    /// Set on timeline entries that are there because someone the viewer
+    /// follows retweeted them.
+    pub retweeted_by: Option<UserResponse>,
     pub user: UserResponse,
 }
 
+/// A tweet in context. Tweets the viewer isn't allowed to see are left out
+/// of both lists.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConversationResponse {
+    /// The tweets `tweet` replies to, oldest first, from the start of the
+    /// conversation down to its direct parent.
+    pub ancestors: Vec<TweetResponse>,
+    pub tweet: TweetResponse,
+    /// A page of the replies below `tweet`, at any depth, oldest first. Their
+    /// `in_reply_to_id` gives the tree.
+    pub replies: Vec<TweetResponse>,
+}
+
+/// An earlier text of an edited tweet.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TweetRevisionResponse {
+    pub id: Uuid,
+    pub text: String,
+    /// When this text was posted, either with the tweet or by an earlier edit.
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PostTweetResponse {
     pub id: Uuid,
     pub text: String,
 }
 
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Whether this is the session the request was made with.
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountDeletionResponse {
+    /// When the account and everything in it is removed for good. Logging in
+    /// before then cancels the deletion. `None` if it's already gone.
+    pub purge_at: Option<DateTime<Utc>>,
+}
+
+/// The contents of `GET /me/export`, and of `export.json` inside the zip
+/// version. The tweets are oldest first.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExportArchive {
+    pub user: UserResponse,
+    pub exported_at: DateTime<Utc>,
+    pub tweets: Vec<TweetResponse>,
+    pub following: Vec<UserResponse>,
+    pub followers: Vec<UserResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FollowRequestResponse {
+    pub id: Uuid,
+    /// Who wants to follow.
+    pub user: UserResponse,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MutedUserResponse {
+    pub user: UserResponse,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MutedKeywordResponse {
+    pub id: Uuid,
+    pub keyword: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}